@@ -0,0 +1,198 @@
+use crate::table::Street;
+
+/// How much a player may bet or raise
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum BettingStructure {
+    /// Any size from a min-raise up to the whole stack
+    #[default]
+    NoLimit,
+    /// Any size from a min-raise up to the size of the pot
+    PotLimit,
+    /// Fixed sizes: small_bet preflop and on the flop, big_bet on the turn and river,
+    /// at most cap bets and raises per street, the big blind counting as the first
+    FixedLimit {
+        small_bet: u64,
+        big_bet: u64,
+        cap: u8,
+    },
+}
+
+/// Betting state seen by the player to act
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BettingSpot {
+    pub street: Street,
+    pub big_blind: u64,
+    /// Every chip in the middle, bets of the current street included
+    pub pot: u64,
+    /// Street total to match
+    pub current_bet: u64,
+    /// Size of the last full bet or raise of the street, a raise must be at least as big
+    pub last_raise: u64,
+    /// Chips the player put in on this street
+    pub street_bet: u64,
+    pub stack: u64,
+    /// Full bets and raises made on this street, short all-ins excluded
+    pub raises: u8,
+}
+
+impl BettingStructure {
+    /// Minimum size of the first bet of a street, and of a raise until someone bets more
+    pub fn opening_size(self, street: Street, big_blind: u64) -> u64 {
+        match self {
+            BettingStructure::FixedLimit {
+                small_bet, big_bet, ..
+            } => match street {
+                Street::Preflop | Street::Flop => small_bet,
+                Street::Turn | Street::River => big_bet,
+            },
+            _ => big_blind,
+        }
+    }
+
+    /// Street totals (min, max) the player may bet or raise to
+    ///
+    /// a stack too short for the minimum may still go all-in, None when the player
+    /// can't put in more than a call
+    pub fn raise_range(self, spot: &BettingSpot) -> Option<(u64, u64)> {
+        let all_in = spot.street_bet + spot.stack;
+        if all_in <= spot.current_bet {
+            return None;
+        }
+
+        let min = spot.current_bet + spot.last_raise;
+        let max = match self {
+            BettingStructure::NoLimit => all_in,
+            BettingStructure::PotLimit => {
+                // call first, then raise by the size of the pot
+                let to_call = spot.current_bet - spot.street_bet;
+                spot.current_bet + spot.pot + to_call
+            }
+            BettingStructure::FixedLimit { cap, .. } => {
+                if spot.raises >= cap {
+                    return None;
+                }
+                spot.current_bet + self.opening_size(spot.street, spot.big_blind)
+            }
+        };
+
+        Some((min.min(all_in), max.max(min).min(all_in)))
+    }
+}
+
+#[cfg(test)]
+mod betting_tests {
+    use super::{BettingSpot, BettingStructure};
+    use crate::table::Street;
+
+    fn spot(pot: u64, current_bet: u64, street_bet: u64, stack: u64) -> BettingSpot {
+        BettingSpot {
+            street: Street::Flop,
+            big_blind: 10,
+            pot,
+            current_bet,
+            last_raise: current_bet.max(10),
+            street_bet,
+            stack,
+            raises: u8::from(current_bet > 0),
+        }
+    }
+
+    #[test]
+    fn test_no_limit() {
+        let no_limit = BettingStructure::NoLimit;
+        assert_eq!(no_limit.raise_range(&spot(100, 0, 0, 500)), Some((10, 500)));
+        assert_eq!(
+            no_limit.raise_range(&spot(150, 50, 0, 500)),
+            Some((100, 500))
+        );
+        // short stacks can only go all-in
+        assert_eq!(no_limit.raise_range(&spot(150, 50, 0, 80)), Some((80, 80)));
+        assert_eq!(no_limit.raise_range(&spot(150, 50, 0, 50)), None);
+    }
+
+    #[test]
+    fn test_pot_limit() {
+        let pot_limit = BettingStructure::PotLimit;
+        // preflop 5/10 blinds: call 10 and raise 25, to 35
+        let preflop = BettingSpot {
+            street: Street::Preflop,
+            big_blind: 10,
+            pot: 15,
+            current_bet: 10,
+            last_raise: 10,
+            street_bet: 0,
+            stack: 1000,
+            raises: 1,
+        };
+        assert_eq!(pot_limit.raise_range(&preflop), Some((20, 35)));
+        // the small blind already has 5 in
+        let small_blind = BettingSpot {
+            street_bet: 5,
+            ..preflop
+        };
+        assert_eq!(pot_limit.raise_range(&small_blind), Some((20, 30)));
+        [
+            // bet the pot
+            (spot(100, 0, 0, 500), Some((10, 100))),
+            // 100 pot, 50 bet: call 50 making 200, raise 200 more
+            (spot(150, 50, 0, 500), Some((100, 250))),
+            // facing a raise from 50 to 150 after betting 50: 300 in the pot
+            (
+                BettingSpot {
+                    last_raise: 100,
+                    ..spot(300, 150, 50, 500)
+                },
+                Some((250, 550)),
+            ),
+            // the stack caps the pot
+            (spot(150, 50, 0, 180), Some((100, 180))),
+            (spot(150, 50, 0, 70), Some((70, 70))),
+        ]
+        .into_iter()
+        .for_each(|(spot, expect)| {
+            assert_eq!(pot_limit.raise_range(&spot), expect, "{:?}", spot);
+        });
+    }
+
+    #[test]
+    fn test_fixed_limit() {
+        let limit = BettingStructure::FixedLimit {
+            small_bet: 10,
+            big_bet: 20,
+            cap: 4,
+        };
+        assert_eq!(limit.opening_size(Street::Preflop, 10), 10);
+        assert_eq!(limit.opening_size(Street::Turn, 10), 20);
+
+        let flop = BettingSpot {
+            last_raise: 10,
+            ..spot(100, 0, 0, 500)
+        };
+        assert_eq!(limit.raise_range(&flop), Some((10, 10)));
+        let raised = BettingSpot {
+            current_bet: 30,
+            raises: 3,
+            ..flop
+        };
+        assert_eq!(limit.raise_range(&raised), Some((40, 40)));
+        // capped after four bets
+        let capped = BettingSpot {
+            current_bet: 40,
+            raises: 4,
+            ..flop
+        };
+        assert_eq!(limit.raise_range(&capped), None);
+
+        let turn = BettingSpot {
+            street: Street::Turn,
+            current_bet: 20,
+            last_raise: 20,
+            raises: 1,
+            ..flop
+        };
+        assert_eq!(limit.raise_range(&turn), Some((40, 40)));
+        // all-in for less than a full bet
+        let short = BettingSpot { stack: 25, ..turn };
+        assert_eq!(limit.raise_range(&short), Some((25, 25)));
+    }
+}
@@ -0,0 +1,246 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    coreutils::{evaluate_best, Card, HandRank},
+    range::{Combo, Range},
+    table::{Action, Event, LegalActions, Street},
+};
+
+/// What a player sees when it is its turn, chip amounts are street totals like Action
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Observation {
+    pub seat: usize,
+    pub hole: [Card; 2],
+    pub board: Vec<Card>,
+    pub street: Street,
+    pub pot: u64,
+    pub stack: u64,
+    pub current_bet: u64,
+    pub big_blind: u64,
+    pub legal: LegalActions,
+}
+
+impl Observation {
+    /// Bet or raise to amount, moved into the legal range, a call when no raise is allowed
+    pub fn bet_or_raise(&self, amount: u64) -> Action {
+        match (self.legal.bet, self.legal.raise) {
+            (Some((min, max)), _) => Action::Bet(amount.clamp(min, max)),
+            (_, Some((min, max))) => Action::Raise(amount.clamp(min, max)),
+            _ => self.check_or_call(),
+        }
+    }
+
+    pub fn check_or_call(&self) -> Action {
+        if self.legal.can_check() {
+            Action::Check
+        } else {
+            Action::Call
+        }
+    }
+
+    pub fn check_or_fold(&self) -> Action {
+        if self.legal.can_check() {
+            Action::Check
+        } else {
+            Action::Fold
+        }
+    }
+}
+
+/// A strategy seated at the table
+pub trait Player {
+    fn name(&self) -> &str;
+
+    /// Decide the next action, an illegal one is replaced by a check or a fold
+    fn act(&mut self, observation: &Observation) -> Action;
+
+    /// Every public event of the table, plus the player's own hole cards
+    fn observe(&mut self, _event: &Event) {}
+}
+
+/// Picks any legal action at random, sizes included
+#[derive(Debug, Clone)]
+pub struct RandomBot {
+    rng: StdRng,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> RandomBot {
+        RandomBot {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Player for RandomBot {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn act(&mut self, observation: &Observation) -> Action {
+        let legal = &observation.legal;
+        let mut actions = vec![observation.check_or_call()];
+        if !legal.can_check() {
+            actions.push(Action::Fold);
+        }
+        if let Some((min, max)) = legal.bet {
+            actions.push(Action::Bet(self.rng.gen_range(min..=max)));
+        }
+        if let Some((min, max)) = legal.raise {
+            actions.push(Action::Raise(self.rng.gen_range(min..=max)));
+        }
+        actions[self.rng.gen_range(0..actions.len())]
+    }
+}
+
+/// Never folds and never raises
+#[derive(Debug, Clone, Default)]
+pub struct CallingStation;
+
+impl Player for CallingStation {
+    fn name(&self) -> &str {
+        "calling station"
+    }
+
+    fn act(&mut self, observation: &Observation) -> Action {
+        observation.check_or_call()
+    }
+}
+
+/// Tight-aggressive: raises a narrow opening range, then bets its made hands
+///
+/// postflop two pair or better bets and raises three quarters of the pot,
+/// a pair made with a hole card bets half the pot and calls up to half the pot,
+/// anything else checks or folds
+#[derive(Debug, Clone)]
+pub struct TightAggressive {
+    opening: Range,
+}
+
+impl TightAggressive {
+    pub fn new() -> TightAggressive {
+        TightAggressive::with_range("77+,A9s+,KTs+,QJs,AJo+,KQo".parse().unwrap())
+    }
+
+    /// Use another preflop opening range
+    pub fn with_range(opening: Range) -> TightAggressive {
+        TightAggressive { opening }
+    }
+}
+
+impl Default for TightAggressive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Player for TightAggressive {
+    fn name(&self) -> &str {
+        "tight aggressive"
+    }
+
+    fn act(&mut self, observation: &Observation) -> Action {
+        let [a, b] = observation.hole;
+        if observation.street == Street::Preflop {
+            if self.opening.weight(&Combo::new(a, b)).is_none() {
+                return observation.check_or_fold();
+            }
+            let open = (observation.current_bet * 3).max(observation.big_blind * 3);
+            return observation.bet_or_raise(open);
+        }
+
+        let mut cards = observation.board.clone();
+        cards.extend_from_slice(&observation.hole);
+        // the flop gives at least five cards
+        let value = evaluate_best(&cards).unwrap().value;
+        let to_call = observation.legal.call;
+        let pot = observation.pot;
+        if value.rank >= HandRank::TwoPair {
+            observation.bet_or_raise(observation.current_bet + pot * 3 / 4)
+        } else if value.rank == HandRank::OnePair
+            && [a, b].iter().any(|card| card.rank() == value.kickers[0])
+        {
+            if observation.legal.can_check() {
+                observation.bet_or_raise(pot / 2)
+            } else if to_call * 2 <= pot {
+                Action::Call
+            } else {
+                Action::Fold
+            }
+        } else {
+            observation.check_or_fold()
+        }
+    }
+}
+
+#[cfg(test)]
+mod bot_tests {
+    use super::{Observation, Player, TightAggressive};
+    use crate::{
+        table::{Action, LegalActions, Street},
+        test_utils::cards,
+    };
+
+    fn observation(hole: &str, board: &str, current_bet: u64) -> Observation {
+        let hole = cards(hole);
+        let street = match board.len() / 2 {
+            0 => Street::Preflop,
+            3 => Street::Flop,
+            4 => Street::Turn,
+            _ => Street::River,
+        };
+        let (bet, raise) = match current_bet {
+            0 => (Some((2, 200)), None),
+            _ => (None, Some((current_bet * 2, 200))),
+        };
+        Observation {
+            seat: 0,
+            hole: [hole[0], hole[1]],
+            board: cards(board),
+            street,
+            pot: 20,
+            stack: 200,
+            current_bet,
+            big_blind: 2,
+            legal: LegalActions {
+                seat: 0,
+                call: current_bet,
+                bet,
+                raise,
+                all_in: true,
+            },
+        }
+    }
+
+    #[test]
+    fn test_tight_aggressive() {
+        let mut bot = TightAggressive::new();
+        [
+            // preflop: raise the range, fold the rest
+            (observation("AsKd", "", 2), Action::Raise(6)),
+            (observation("7s7d", "", 8), Action::Raise(24)),
+            (observation("7s2d", "", 2), Action::Fold),
+            // two pair bets three quarters of the pot
+            (observation("AsKd", "AhKc2d", 0), Action::Bet(15)),
+            // top pair bets half, calls a small bet and folds to a big one
+            (observation("AsQd", "Ah7c2d", 0), Action::Bet(10)),
+            (observation("AsQd", "Ah7c2d", 10), Action::Call),
+            (observation("AsQd", "Ah7c2d", 30), Action::Fold),
+            // a pair on the board only is nothing
+            (observation("QsJd", "AhAc2d", 0), Action::Check),
+            (observation("QsJd", "AhAc2d", 4), Action::Fold),
+        ]
+        .into_iter()
+        .for_each(|(observation, expect)| {
+            assert_eq!(bot.act(&observation), expect, "{:?}", observation.hole);
+        });
+    }
+
+    #[test]
+    fn test_observation_sizes() {
+        let observation = observation("AsKd", "Ah7c2d", 0);
+        assert_eq!(observation.bet_or_raise(1), Action::Bet(2));
+        assert_eq!(observation.bet_or_raise(1000), Action::Bet(200));
+        assert_eq!(observation.check_or_fold(), Action::Check);
+    }
+}
@@ -0,0 +1,298 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub, SubAssign};
+
+use super::{Card, Hand, Suits};
+
+/// Set of cards as a bitmask, one bit per card at `Card::to_index`
+///
+/// Spades take bits 0-12, Hearts 13-25, Diamonds 26-38 and Clubs 39-51,
+/// each suit ordered A-K. Every operation is allocation free.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub struct CardSet(pub u64);
+
+const SUIT_BITS: u64 = (1 << 13) - 1;
+
+impl CardSet {
+    pub const EMPTY: CardSet = CardSet(0);
+    pub const FULL: CardSet = CardSet((1 << 52) - 1);
+
+    pub fn new() -> CardSet {
+        CardSet::EMPTY
+    }
+
+    /// Every card of one suit
+    pub fn suit_mask(suit: Suits) -> CardSet {
+        CardSet(SUIT_BITS << (suit as u64 * 13))
+    }
+
+    /// The four cards of one num (1-13, Ace as 1)
+    pub fn rank_mask(num: u8) -> CardSet {
+        if !(1..=13).contains(&num) {
+            return CardSet::EMPTY;
+        }
+        let bit = 1u64 << (num - 1);
+        CardSet(bit | bit << 13 | bit << 26 | bit << 39)
+    }
+
+    pub fn insert(&mut self, card: Card) {
+        self.0 |= CardSet::from(card).0;
+    }
+
+    pub fn remove(&mut self, card: Card) {
+        self.0 &= !CardSet::from(card).0;
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        let bit = CardSet::from(card).0;
+        bit != 0 && self.0 & bit == bit
+    }
+
+    pub fn union(self, other: CardSet) -> CardSet {
+        CardSet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: CardSet) -> CardSet {
+        CardSet(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: CardSet) -> CardSet {
+        CardSet(self.0 & !other.0)
+    }
+
+    pub fn is_disjoint(self, other: CardSet) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    /// Number of cards, a popcount
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Cards of one suit as a 13 bit num mask (bit 0 is the Ace)
+    pub fn suit_nums(&self, suit: Suits) -> u16 {
+        ((self.0 >> (suit as u64 * 13)) & SUIT_BITS) as u16
+    }
+
+    /// Nums present in any suit as a 13 bit mask (bit 0 is the Ace)
+    pub fn nums(&self) -> u16 {
+        Suits::ALL
+            .into_iter()
+            .fold(0, |nums, suit| nums | self.suit_nums(suit))
+    }
+
+    /// Cards ordered by `Card::to_index`
+    pub fn iter(&self) -> CardSetIter {
+        CardSetIter(self.0)
+    }
+
+    pub fn to_vec(&self) -> Vec<Card> {
+        self.iter().collect()
+    }
+}
+
+/// Iterator over a CardSet, lowest index first
+#[derive(Debug, Clone)]
+pub struct CardSetIter(u64);
+
+impl Iterator for CardSetIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros() as u8;
+        self.0 &= self.0 - 1;
+        Card::from_index(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CardSetIter {}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = CardSetIter;
+
+    fn into_iter(self) -> CardSetIter {
+        self.iter()
+    }
+}
+
+impl From<Card> for CardSet {
+    /// Unknown cards (num 0) give an empty set
+    fn from(card: Card) -> Self {
+        card.to_index()
+            .map_or(CardSet::EMPTY, |index| CardSet(1 << index))
+    }
+}
+
+impl From<&[Card]> for CardSet {
+    fn from(cards: &[Card]) -> Self {
+        cards.iter().copied().collect()
+    }
+}
+
+impl From<&Hand> for CardSet {
+    fn from(hand: &Hand) -> Self {
+        CardSet::from(hand.cards.as_slice())
+    }
+}
+
+impl From<CardSet> for Hand {
+    fn from(set: CardSet) -> Self {
+        Hand {
+            cards: set.to_vec(),
+        }
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        let mut set = CardSet::new();
+        iter.into_iter().for_each(|card| set.insert(card));
+        set
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = CardSet;
+
+    fn bitor(self, rhs: CardSet) -> CardSet {
+        self.union(rhs)
+    }
+}
+
+impl BitOrAssign for CardSet {
+    fn bitor_assign(&mut self, rhs: CardSet) {
+        *self = self.union(rhs);
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = CardSet;
+
+    fn bitand(self, rhs: CardSet) -> CardSet {
+        self.intersection(rhs)
+    }
+}
+
+impl BitAndAssign for CardSet {
+    fn bitand_assign(&mut self, rhs: CardSet) {
+        *self = self.intersection(rhs);
+    }
+}
+
+impl Sub for CardSet {
+    type Output = CardSet;
+
+    fn sub(self, rhs: CardSet) -> CardSet {
+        self.difference(rhs)
+    }
+}
+
+impl SubAssign for CardSet {
+    fn sub_assign(&mut self, rhs: CardSet) {
+        *self = self.difference(rhs);
+    }
+}
+
+impl Not for CardSet {
+    type Output = CardSet;
+
+    /// Complement within the 52 card deck
+    fn not(self) -> CardSet {
+        CardSet::FULL.difference(self)
+    }
+}
+
+#[cfg(test)]
+mod card_set_tests {
+    use std::str::FromStr;
+
+    use super::CardSet;
+    use crate::coreutils::{Card, Hand, Suits};
+
+    fn set(cards: &[&str]) -> CardSet {
+        CardSet::from(Card::from_strs(cards.to_vec()).unwrap().as_slice())
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set = CardSet::new();
+        let ace = Card::from_str("As").unwrap();
+        assert!(set.is_empty());
+        set.insert(ace);
+        set.insert(ace);
+        assert_eq!(set.len(), 1);
+        assert!(set.contains(ace));
+        set.remove(ace);
+        assert!(set.is_empty());
+
+        // unknown cards are never in a set
+        let unknown = Card::from(Suits::Spades, 0);
+        set.insert(unknown);
+        assert!(set.is_empty());
+        assert!(!CardSet::FULL.contains(unknown));
+    }
+
+    #[test]
+    fn test_ops() {
+        let a = set(&["As", "Kh", "2c"]);
+        let b = set(&["Kh", "2c", "7d"]);
+        assert_eq!(a | b, set(&["As", "Kh", "2c", "7d"]));
+        assert_eq!(a & b, set(&["Kh", "2c"]));
+        assert_eq!(a - b, set(&["As"]));
+        assert_eq!((!a).len(), 49);
+        assert!((a - b).is_disjoint(b));
+
+        let mut c = a;
+        c |= b;
+        c -= a;
+        assert_eq!(c, set(&["7d"]));
+        c &= a;
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn test_masks() {
+        assert_eq!(CardSet::FULL.len(), 52);
+        Suits::ALL.into_iter().for_each(|suit| {
+            let mask = CardSet::suit_mask(suit);
+            assert_eq!(mask.len(), 13);
+            assert!(mask.iter().all(|card| card.suit == suit));
+        });
+        (1..=13).for_each(|num| {
+            let mask = CardSet::rank_mask(num);
+            assert_eq!(mask.len(), 4);
+            assert!(mask.iter().all(|card| card.num == num));
+        });
+        assert!(CardSet::rank_mask(0).is_empty());
+
+        let cards = set(&["As", "Ah", "Kh", "2h"]);
+        assert_eq!(cards.suit_nums(Suits::Hearts), 1 | 1 << 12 | 1 << 1);
+        assert_eq!(cards.suit_nums(Suits::Clubs), 0);
+        assert_eq!(cards.nums(), 1 | 1 << 12 | 1 << 1);
+    }
+
+    #[test]
+    fn test_iter_and_hand() {
+        let hand = Hand::from_str("Kc2sAhTd5s").unwrap();
+        let set = CardSet::from(&hand);
+        assert_eq!(set.iter().len(), 5);
+        assert_eq!(
+            set.to_vec(),
+            Card::from_strs(vec!["2s", "5s", "Ah", "Td", "Kc"]).unwrap()
+        );
+        assert_eq!(CardSet::from(&Hand::from(set)), set);
+        assert_eq!(set.into_iter().collect::<CardSet>(), set);
+        assert_eq!(CardSet::FULL.iter().count(), 52);
+    }
+}
@@ -1,8 +1,10 @@
-use std::hash::Hash;
+use std::{cmp::Ordering, fmt, str::FromStr};
 
 use rand::{rngs::ThreadRng, Rng};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+use super::{CardSet, Variant};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum Suits {
     Spades,
     Hearts,
@@ -10,7 +12,114 @@ pub enum Suits {
     Clubs,
 }
 
+impl Suits {
+    /// Every suit in deck order
+    pub const ALL: [Suits; 4] = [Suits::Spades, Suits::Hearts, Suits::Diamonds, Suits::Clubs];
+
+    fn from_char(c: char) -> Option<Suits> {
+        match c {
+            's' => Some(Suits::Spades),
+            'h' => Some(Suits::Hearts),
+            'd' => Some(Suits::Diamonds),
+            'c' => Some(Suits::Clubs),
+            _ => None,
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Suits::Spades => 's',
+            Suits::Hearts => 'h',
+            Suits::Diamonds => 'd',
+            Suits::Clubs => 'c',
+        }
+    }
+}
+
+impl fmt::Display for Suits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl FromStr for Suits {
+    type Err = ParseCardError;
+
+    /// s => Spades, h => Hearts, d => Diamonds, c => Clubs
+    fn from_str(s: &str) -> Result<Suits, ParseCardError> {
+        let mut chars = s.chars();
+        let c = chars
+            .next()
+            .ok_or(ParseCardError::new(ParseCardErrorKind::MissingSuit, 0))?;
+        let suit = Suits::from_char(c)
+            .ok_or(ParseCardError::new(ParseCardErrorKind::InvalidSuit(c), 0))?;
+        match chars.next() {
+            Some(c) => Err(ParseCardError::new(ParseCardErrorKind::TrailingInput(c), 1)),
+            None => Ok(suit),
+        }
+    }
+}
+
+impl TryFrom<&str> for Suits {
+    type Error = ParseCardError;
+
+    fn try_from(s: &str) -> Result<Suits, ParseCardError> {
+        s.parse()
+    }
+}
+
+/// What went wrong when parsing cards
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ParseCardErrorKind {
+    /// Input ended where a rank was expected
+    MissingRank,
+    /// Input ended where a suit was expected
+    MissingSuit,
+    InvalidRank(char),
+    InvalidSuit(char),
+    /// Extra characters after a complete card
+    TrailingInput(char),
+    /// A hand needs exactly five cards
+    WrongCardCount(usize),
+}
+
+/// Error from parsing a Card, Suits or Hand
+///
+/// position is the index of the offending character (not byte) in the input
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ParseCardError {
+    pub kind: ParseCardErrorKind,
+    pub position: usize,
+}
+
+impl ParseCardError {
+    fn new(kind: ParseCardErrorKind, position: usize) -> ParseCardError {
+        ParseCardError { kind, position }
+    }
+
+    fn shift(self, offset: usize) -> ParseCardError {
+        ParseCardError::new(self.kind, self.position + offset)
+    }
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseCardErrorKind::MissingRank => write!(f, "missing rank")?,
+            ParseCardErrorKind::MissingSuit => write!(f, "missing suit")?,
+            ParseCardErrorKind::InvalidRank(c) => write!(f, "invalid rank {:?}", c)?,
+            ParseCardErrorKind::InvalidSuit(c) => write!(f, "invalid suit {:?}", c)?,
+            ParseCardErrorKind::TrailingInput(c) => write!(f, "unexpected {:?}", c)?,
+            ParseCardErrorKind::WrongCardCount(n) => write!(f, "expect 5 cards, found {}", n)?,
+        }
+        write!(f, " at position {}", self.position)
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+/// Card ordering follows rank (Ace high), then suit, then num so it agrees with Eq
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Card {
     pub suit: Suits,
     pub num: u8, // 1-13, 0 for unknown
@@ -21,25 +130,57 @@ impl Card {
         Card { suit, num }
     }
 
-    /// Create Card with &str
+    /// Rank used for comparison: 2-13 as is, Ace counts as 14
+    pub fn rank(&self) -> u8 {
+        if self.num == 1 {
+            14
+        } else {
+            self.num
+        }
+    }
+
+    /// Position in a fresh deck: Spades A-K, Hearts A-K, Diamonds A-K, Clubs A-K
     ///
-    /// As => Spade 1 \
-    /// Th => Heart 1 \
-    /// Qd => Diamond 12 \
-    /// 7c => Club 7
-    #[allow(clippy::should_implement_trait)]
-    pub fn from_str(s: &str) -> Option<Card> {
-        let bytes = s.as_bytes();
-        let num_char = bytes[0] as char;
-        let suit_char = bytes[1] as char;
-
-        let suit = match suit_char {
-            's' => Suits::Spades,
-            'h' => Suits::Hearts,
-            'd' => Suits::Diamonds,
-            'c' => Suits::Clubs,
+    /// return None for the unknown card (num 0) or a num above 13
+    pub fn to_index(&self) -> Option<u8> {
+        (1..=13)
+            .contains(&self.num)
+            .then(|| self.suit as u8 * 13 + self.num - 1)
+    }
+
+    /// Reverse action of to_index
+    pub fn from_index(index: u8) -> Option<Card> {
+        let suit = match index / 13 {
+            0 => Suits::Spades,
+            1 => Suits::Hearts,
+            2 => Suits::Diamonds,
+            3 => Suits::Clubs,
             _ => return None,
         };
+
+        Some(Card::from(suit, index % 13 + 1))
+    }
+
+    pub fn from_strs(list: Vec<&str>) -> Result<Vec<Card>, ParseCardError> {
+        list.into_iter().map(str::parse).collect()
+    }
+
+    /// Parse cards written back to back, like "AhKd7c"
+    pub fn parse_many(s: &str) -> Result<Vec<Card>, ParseCardError> {
+        let mut chars = s.chars();
+        let mut cards = vec![];
+        while !chars.as_str().is_empty() {
+            let offset = cards.len() * 2;
+            cards.push(Card::parse_next(&mut chars).map_err(|err| err.shift(offset))?);
+        }
+        Ok(cards)
+    }
+
+    /// Parse the first card of chars, leaving the rest untouched
+    fn parse_next(chars: &mut std::str::Chars) -> Result<Card, ParseCardError> {
+        let num_char = chars
+            .next()
+            .ok_or(ParseCardError::new(ParseCardErrorKind::MissingRank, 0))?;
         let num: u8 = match num_char {
             'A' => 1,
             'T' => 10,
@@ -47,25 +188,56 @@ impl Card {
             'Q' => 12,
             'K' => 13,
             '2'..='9' => num_char as u8 - b'0',
-            _ => return None,
+            _ => {
+                return Err(ParseCardError::new(
+                    ParseCardErrorKind::InvalidRank(num_char),
+                    0,
+                ))
+            }
         };
 
-        Some(Card::from(suit, num))
+        let suit_char = chars
+            .next()
+            .ok_or(ParseCardError::new(ParseCardErrorKind::MissingSuit, 1))?;
+        let suit = Suits::from_char(suit_char).ok_or(ParseCardError::new(
+            ParseCardErrorKind::InvalidSuit(suit_char),
+            1,
+        ))?;
+
+        Ok(Card::from(suit, num))
     }
+}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    /// Create Card with &str
+    ///
+    /// As => Spade 1 \
+    /// Th => Heart 1 \
+    /// Qd => Diamond 12 \
+    /// 7c => Club 7
+    fn from_str(s: &str) -> Result<Card, ParseCardError> {
+        let mut chars = s.chars();
+        let card = Card::parse_next(&mut chars)?;
+        match chars.next() {
+            Some(c) => Err(ParseCardError::new(ParseCardErrorKind::TrailingInput(c), 2)),
+            None => Ok(card),
+        }
+    }
+}
+
+impl TryFrom<&str> for Card {
+    type Error = ParseCardError;
 
-    pub fn from_strs(list: Vec<&str>) -> Option<Vec<Card>> {
-        list.into_iter().map(Card::from_str).collect()
+    fn try_from(s: &str) -> Result<Card, ParseCardError> {
+        s.parse()
     }
+}
 
+impl fmt::Display for Card {
     /// Reverse action of from_str
-    #[allow(clippy::inherent_to_string)]
-    pub fn to_string(&self) -> String {
-        let suit_char = match self.suit {
-            Suits::Spades => 's',
-            Suits::Hearts => 'h',
-            Suits::Diamonds => 'd',
-            Suits::Clubs => 'c',
-        };
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let num_char = match self.num {
             1 => 'A',
             10 => 'T',
@@ -75,19 +247,27 @@ impl Card {
             n => (n + b'0') as char,
         };
 
-        format!("{}{}", num_char, suit_char)
+        write!(f, "{}{}", num_char, self.suit)
     }
 }
 
-impl Hash for Card {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        state.write(&[self.num, self.suit as u8]);
+impl PartialOrd for Card {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Card {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.rank(), self.suit, self.num).cmp(&(other.rank(), other.suit, other.num))
     }
 }
 
 #[cfg(test)]
 mod card_tests {
-    use super::{Card, Suits};
+    use std::str::FromStr;
+
+    use super::{Card, ParseCardError, ParseCardErrorKind, Suits};
 
     #[test]
     fn test_from_str() {
@@ -116,6 +296,76 @@ mod card_tests {
         });
     }
 
+    #[test]
+    fn test_from_str_error() {
+        [
+            ("", ParseCardErrorKind::MissingRank, 0),
+            ("A", ParseCardErrorKind::MissingSuit, 1),
+            ("1s", ParseCardErrorKind::InvalidRank('1'), 0),
+            ("as", ParseCardErrorKind::InvalidRank('a'), 0),
+            ("Ax", ParseCardErrorKind::InvalidSuit('x'), 1),
+            ("AS", ParseCardErrorKind::InvalidSuit('S'), 1),
+            ("Asd", ParseCardErrorKind::TrailingInput('d'), 2),
+            ("A♠", ParseCardErrorKind::InvalidSuit('♠'), 1),
+            ("♠A", ParseCardErrorKind::InvalidRank('♠'), 0),
+        ]
+        .into_iter()
+        .for_each(|(s, kind, position)| {
+            assert_eq!(
+                Card::from_str(s),
+                Err(ParseCardError { kind, position }),
+                "{}",
+                s
+            );
+        });
+        assert_eq!(
+            Card::from_strs(vec!["As", "Kx"]).unwrap_err().kind,
+            ParseCardErrorKind::InvalidSuit('x')
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        (0..52).for_each(|i| {
+            let card = Card::from_index(i).unwrap();
+            assert_eq!(Card::try_from(card.to_string().as_str()), Ok(card));
+        });
+        assert_eq!(Card::from(Suits::Hearts, 10).to_string(), "Th");
+        assert_eq!(Suits::from_str("d"), Ok(Suits::Diamonds));
+        assert_eq!(Suits::try_from("c").unwrap().to_string(), "c");
+        assert!(Suits::from_str("").is_err());
+        assert!(Suits::from_str("sh").is_err());
+    }
+
+    #[test]
+    fn test_ord() {
+        let mut cards = Card::from_strs(vec!["As", "2c", "Kh", "2s", "Ah", "Td"]).unwrap();
+        cards.sort();
+        assert_eq!(
+            cards,
+            Card::from_strs(vec!["2s", "2c", "Td", "Kh", "As", "Ah"]).unwrap()
+        );
+        assert!(Suits::Spades < Suits::Clubs);
+
+        // an out of range num shares the Ace's rank but is not the same card
+        let ace = Card::from(Suits::Spades, 1);
+        let invalid = Card::from(Suits::Spades, 14);
+        assert_eq!(ace.rank(), invalid.rank());
+        assert_ne!(ace.cmp(&invalid), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_index() {
+        (0..52).for_each(|i| {
+            assert_eq!(Card::from_index(i).unwrap().to_index(), Some(i));
+        });
+        assert_eq!(Card::from(Suits::Spades, 0).to_index(), None);
+        assert_eq!(Card::from(Suits::Clubs, 14).to_index(), None);
+        assert_eq!(Card::from_index(0).unwrap(), Card::from(Suits::Spades, 1));
+        assert_eq!(Card::from_index(51).unwrap(), Card::from(Suits::Clubs, 13));
+        assert!(Card::from_index(52).is_none());
+    }
+
     #[test]
     fn test_from_strs() {
         let cards = Card::from_strs(vec!["As", "2s", "3s", "4s", "5s"]).unwrap();
@@ -131,12 +381,62 @@ mod card_tests {
             ]
         )
     }
+
+    #[test]
+    fn test_parse_many() {
+        assert_eq!(
+            Card::parse_many("AsKd7c").unwrap(),
+            Card::from_strs(vec!["As", "Kd", "7c"]).unwrap()
+        );
+        assert_eq!(Card::parse_many("").unwrap(), vec![]);
+        [
+            ("AsKx", ParseCardErrorKind::InvalidSuit('x'), 3),
+            ("AsK", ParseCardErrorKind::MissingSuit, 3),
+            ("As1d", ParseCardErrorKind::InvalidRank('1'), 2),
+        ]
+        .into_iter()
+        .for_each(|(s, kind, position)| {
+            assert_eq!(
+                Card::parse_many(s),
+                Err(ParseCardError { kind, position }),
+                "{}",
+                s
+            );
+        });
+    }
 }
 
-#[derive(Debug)]
-pub struct CardDeck {
-    rng: ThreadRng,
-    rest: Vec<u8>,
+/// Errors when asking a CardDeck for specific cards
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DeckError {
+    /// The card is no longer in the deck (dealt, burnt or removed before)
+    AlreadyDealt(Card),
+    /// The card does not exist in the deck's variant
+    InvalidCard(Card),
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeckError::AlreadyDealt(card) => write!(f, "card {:?} was already dealt", card),
+            DeckError::InvalidCard(card) => write!(f, "card {:?} is not in this deck", card),
+        }
+    }
+}
+
+impl std::error::Error for DeckError {}
+
+/// A deck of 52 cards dealt from the top
+///
+/// Generic over the random source, so a seeded rng (e.g. `StdRng::seed_from_u64`)
+/// replays the exact same deals.
+#[derive(Debug, Clone)]
+pub struct CardDeck<R: Rng = ThreadRng> {
+    rng: R,
+    variant: Variant,
+    cards: Vec<u8>,
+    // cards[..dealt] are gone, cards[dealt..] are still in the deck
+    dealt: usize,
 }
 
 impl Default for CardDeck {
@@ -146,38 +446,137 @@ impl Default for CardDeck {
 }
 
 impl CardDeck {
+    /// Shuffled deck driven by the thread local rng
     pub fn new() -> CardDeck {
-        CardDeck {
-            rng: rand::thread_rng(),
-            rest: (0..52).collect::<Vec<u8>>(),
-        }
+        CardDeck::with_rng(rand::thread_rng())
+    }
+}
+
+impl<R: Rng> CardDeck<R> {
+    /// Shuffled deck driven by the given rng
+    pub fn with_rng(rng: R) -> CardDeck<R> {
+        CardDeck::with_variant(Variant::Standard, rng)
+    }
+
+    /// Shuffled deck holding only the variant's cards, e.g. 36 cards for short deck
+    pub fn with_variant(variant: Variant, rng: R) -> CardDeck<R> {
+        let mut deck = CardDeck {
+            rng,
+            variant,
+            cards: (0..52)
+                .filter(|&i| Card::from_index(i).is_some_and(|card| variant.contains(&card)))
+                .collect::<Vec<u8>>(),
+            dealt: 0,
+        };
+        deck.shuffle();
+        deck
     }
 
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// Fisher-Yates shuffle over the remaining cards
     pub fn shuffle(&mut self) {
-        self.rng = rand::thread_rng();
+        for i in (self.dealt + 1..self.cards.len()).rev() {
+            let j = self.rng.gen_range(self.dealt..=i);
+            self.cards.swap(i, j);
+        }
+    }
+
+    /// Put every card back and shuffle
+    pub fn reset(&mut self) {
+        self.dealt = 0;
+        self.shuffle();
     }
 
     pub fn deal(&mut self) -> Option<Card> {
-        let num = self.rest.remove(self.rng.gen_range(0..self.rest.len()));
-        let suit_seq = num / 13;
-        let num = num % 13 + 1;
-        let suit = match suit_seq {
-            0 => Suits::Spades,
-            1 => Suits::Hearts,
-            2 => Suits::Diamonds,
-            3 => Suits::Clubs,
-            _ => return None,
-        };
+        let card = self.peek()?;
+        self.dealt += 1;
+        Some(card)
+    }
+
+    /// Deal n cards at once, nothing is dealt when less than n cards are left
+    pub fn deal_n(&mut self, n: usize) -> Option<Vec<Card>> {
+        if n > self.len() {
+            return None;
+        }
+        (0..n).map(|_| self.deal()).collect()
+    }
+
+    /// Discard the top card
+    pub fn burn(&mut self) -> Option<Card> {
+        self.deal()
+    }
+
+    /// Cards still in the deck
+    pub fn remaining(&self) -> CardSet {
+        self.cards[self.dealt..]
+            .iter()
+            .filter_map(|&index| Card::from_index(index))
+            .collect()
+    }
+
+    /// Whether the card is still in the deck
+    pub fn contains(&self, card: &Card) -> bool {
+        self.position(card).is_ok()
+    }
 
-        Some(Card::from(suit, num))
+    /// Take a specific card out of the deck, e.g. known hole cards or board
+    pub fn deal_specific(&mut self, card: &Card) -> Result<Card, DeckError> {
+        let i = self.position(card)?;
+        self.cards.swap(self.dealt, i);
+        self.dealt += 1;
+        Ok(*card)
+    }
+
+    /// Remove a dead card so it can't be dealt anymore
+    pub fn remove(&mut self, card: &Card) -> Result<(), DeckError> {
+        self.deal_specific(card).map(|_| ())
+    }
+
+    /// Remove several dead cards, nothing is removed when any of them fails
+    pub fn remove_all(&mut self, cards: &[Card]) -> Result<(), DeckError> {
+        for (i, card) in cards.iter().enumerate() {
+            self.position(card)?;
+            if cards[..i].contains(card) {
+                return Err(DeckError::AlreadyDealt(*card));
+            }
+        }
+        cards.iter().try_for_each(|card| self.remove(card))
+    }
+
+    fn position(&self, card: &Card) -> Result<usize, DeckError> {
+        if !self.variant.contains(card) {
+            return Err(DeckError::InvalidCard(*card));
+        }
+        let index = card.to_index().ok_or(DeckError::InvalidCard(*card))?;
+        self.cards[self.dealt..]
+            .iter()
+            .position(|&i| i == index)
+            .map(|i| i + self.dealt)
+            .ok_or(DeckError::AlreadyDealt(*card))
+    }
+
+    /// The rng driving the shuffles, to draw other random choices from the same seed
+    pub fn rng_mut(&mut self) -> &mut R {
+        &mut self.rng
+    }
+
+    /// Look at the top card without dealing it
+    pub fn peek(&self) -> Option<Card> {
+        self.cards
+            .get(self.dealt)
+            .copied()
+            .and_then(Card::from_index)
     }
 
     pub fn len(&self) -> usize {
-        self.rest.len()
+        self.cards.len() - self.dealt
     }
 
     pub fn is_empty(&self) -> bool {
-        self.rest.is_empty()
+        self.len() == 0
     }
 }
 
@@ -185,7 +584,10 @@ impl CardDeck {
 mod card_deck_test {
     use std::collections::HashSet;
 
-    use crate::coreutils::CardDeck;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::DeckError;
+    use crate::coreutils::{Card, CardDeck, Suits, Variant};
 
     #[test]
     fn test_deal() {
@@ -200,46 +602,302 @@ mod card_deck_test {
         }
         assert_eq!(deal_count, 52);
         assert_eq!(deal_cards.len(), 52);
+        assert!(deck.deal().is_none());
+    }
+
+    #[test]
+    fn test_seeded() {
+        let mut deck1 = CardDeck::with_rng(StdRng::seed_from_u64(42));
+        let mut deck2 = CardDeck::with_rng(StdRng::seed_from_u64(42));
+        assert_eq!(deck1.deal_n(52), deck2.deal_n(52));
+
+        deck1.reset();
+        deck2.reset();
+        assert_eq!(deck1.deal_n(10), deck2.deal_n(10));
+
+        let mut deck3 = CardDeck::with_rng(StdRng::seed_from_u64(43));
+        deck1.reset();
+        assert_ne!(deck1.deal_n(52), deck3.deal_n(52));
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut deck = CardDeck::with_rng(StdRng::seed_from_u64(1));
+        let dealt = deck.deal_n(2).unwrap();
+        let before = (0..deck.len())
+            .map(|i| deck.cards[deck.dealt + i])
+            .collect::<Vec<_>>();
+        deck.shuffle();
+        let after = deck.deal_n(50).unwrap();
+
+        assert_eq!(deck.len(), 0);
+        assert!(after.iter().all(|card| !dealt.contains(card)));
+        assert_ne!(
+            before,
+            after
+                .iter()
+                .map(|card| card.to_index().unwrap())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_peek_burn_deal_n() {
+        let mut deck = CardDeck::with_rng(StdRng::seed_from_u64(2));
+        let top = deck.peek().unwrap();
+        assert_eq!(deck.len(), 52);
+        assert_eq!(deck.burn(), Some(top));
+        assert_eq!(deck.len(), 51);
+
+        let next = deck.peek().unwrap();
+        let cards = deck.deal_n(3).unwrap();
+        assert_eq!(cards.len(), 3);
+        assert_eq!(cards[0], next);
+        assert_eq!(deck.len(), 48);
+
+        assert!(deck.deal_n(49).is_none());
+        assert_eq!(deck.len(), 48);
+        assert_eq!(deck.deal_n(48).unwrap().len(), 48);
+        assert!(deck.peek().is_none());
+
+        deck.reset();
+        assert_eq!(deck.len(), 52);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut deck = CardDeck::with_rng(StdRng::seed_from_u64(3));
+        let hero = Card::from_strs(vec!["As", "Ks"]).unwrap();
+        let flop = Card::from_strs(vec!["Qs", "Js", "2d"]).unwrap();
+
+        deck.remove_all(&hero).unwrap();
+        for card in &flop {
+            assert!(deck.contains(card));
+            assert_eq!(deck.deal_specific(card), Ok(*card));
+            assert!(!deck.contains(card));
+        }
+        assert_eq!(deck.len(), 47);
+
+        let remaining = deck.remaining();
+        assert_eq!(remaining.len(), 47);
+        assert!(hero
+            .iter()
+            .chain(&flop)
+            .all(|card| !remaining.contains(*card)));
+
+        let rest = deck.deal_n(47).unwrap();
+        assert!(rest
+            .iter()
+            .all(|card| !hero.contains(card) && !flop.contains(card)));
+    }
+
+    #[test]
+    fn test_short_deck() {
+        let mut deck = CardDeck::with_variant(Variant::ShortDeck, StdRng::seed_from_u64(5));
+        assert_eq!(deck.len(), 36);
+        assert_eq!(deck.variant(), Variant::ShortDeck);
+
+        let five = Card::from(Suits::Spades, 5);
+        assert_eq!(deck.remove(&five), Err(DeckError::InvalidCard(five)));
+        let cards = deck.deal_n(36).unwrap();
+        assert!(cards.iter().all(|card| card.num == 1 || card.num >= 6));
+
+        deck.reset();
+        assert_eq!(deck.len(), 36);
+    }
+
+    #[test]
+    fn test_remove_error() {
+        let mut deck = CardDeck::with_rng(StdRng::seed_from_u64(4));
+        let ace = Card::from(Suits::Spades, 1);
+        let king = Card::from(Suits::Spades, 13);
+
+        deck.remove(&ace).unwrap();
+        assert_eq!(deck.remove(&ace), Err(DeckError::AlreadyDealt(ace)));
+        assert_eq!(deck.deal_specific(&ace), Err(DeckError::AlreadyDealt(ace)));
+
+        let unknown = Card::from(Suits::Hearts, 0);
+        assert_eq!(deck.remove(&unknown), Err(DeckError::InvalidCard(unknown)));
+
+        // nothing is removed when one card fails
+        assert_eq!(
+            deck.remove_all(&[king, ace]),
+            Err(DeckError::AlreadyDealt(ace))
+        );
+        assert_eq!(
+            deck.remove_all(&[king, king]),
+            Err(DeckError::AlreadyDealt(king))
+        );
+        assert!(deck.contains(&king));
+        assert_eq!(deck.len(), 51);
+
+        let dealt = deck.deal().unwrap();
+        assert_eq!(deck.remove(&dealt), Err(DeckError::AlreadyDealt(dealt)));
     }
 }
 
+/// Hand categories, ordered from the weakest to the strongest
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum HandRank {
-    RoyalFlush,
-    StraightFlush,
-    FourOfAKind,
-    FullHouse,
-    Flush,
-    Straight,
-    ThreeOfAKind,
-    TwoPair,
-    OnePair,
     HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    RoyalFlush,
 }
 
-#[derive(Debug)]
+impl fmt::Display for HandRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HandRank::HighCard => "High card",
+            HandRank::OnePair => "One pair",
+            HandRank::TwoPair => "Two pair",
+            HandRank::ThreeOfAKind => "Three of a kind",
+            HandRank::Straight => "Straight",
+            HandRank::Flush => "Flush",
+            HandRank::FullHouse => "Full house",
+            HandRank::FourOfAKind => "Four of a kind",
+            HandRank::StraightFlush => "Straight flush",
+            HandRank::RoyalFlush => "Royal flush",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Five cards, e.g. "As2s3s4s5s"
+///
+/// Ord compares the cards one by one in their given order, for sorting and sets;
+/// it is not hand strength, use `evaluate` for that
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub struct Hand {
     pub cards: Vec<Card>,
 }
 
-impl Hand {
-    #[allow(clippy::should_implement_trait)]
-    pub fn from_str(s: &str) -> Option<Hand> {
-        if s.len() != 10 {
-            return None;
+impl FromStr for Hand {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Hand, ParseCardError> {
+        let mut chars = s.chars();
+        let mut cards = vec![];
+        while !chars.as_str().is_empty() {
+            let offset = cards.len() * 2;
+            if cards.len() == 5 {
+                let extra = s.chars().count() - offset;
+                return Err(ParseCardError::new(
+                    ParseCardErrorKind::WrongCardCount(5 + extra.div_ceil(2)),
+                    offset,
+                ));
+            }
+            cards.push(Card::parse_next(&mut chars).map_err(|err| err.shift(offset))?);
         }
+        if cards.len() != 5 {
+            return Err(ParseCardError::new(
+                ParseCardErrorKind::WrongCardCount(cards.len()),
+                cards.len() * 2,
+            ));
+        }
+
+        Ok(Hand { cards })
+    }
+}
 
-        let cards = s
-            .chars()
-            .collect::<Vec<_>>()
-            .chunks(2)
-            .map(|inner_s| Card::from_str(inner_s.iter().collect::<String>().as_str()))
-            .collect::<Option<Vec<Card>>>()?;
+impl TryFrom<&str> for Hand {
+    type Error = ParseCardError;
 
-        Some(Hand { cards })
+    fn try_from(s: &str) -> Result<Hand, ParseCardError> {
+        s.parse()
     }
 }
 
-#[test]
-fn test_hand_from_str() {
-    assert!(Hand::from_str("As2s3s4s5s").is_some());
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cards.iter().try_for_each(|card| write!(f, "{}", card))
+    }
+}
+
+#[cfg(test)]
+mod hand_tests {
+    use std::str::FromStr;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{Card, Hand, ParseCardError, ParseCardErrorKind, Suits};
+
+    #[test]
+    fn test_hand_from_str() {
+        assert!(Hand::from_str("As2s3s4s5s").is_ok());
+        assert_eq!(
+            Hand::try_from("As2s3s4s5s").unwrap().to_string(),
+            "As2s3s4s5s"
+        );
+    }
+
+    #[test]
+    fn test_hand_from_str_error() {
+        [
+            ("", ParseCardErrorKind::WrongCardCount(0), 0),
+            ("As2s3s4s", ParseCardErrorKind::WrongCardCount(4), 8),
+            ("As2s3s4s5s6s", ParseCardErrorKind::WrongCardCount(6), 10),
+            ("As2s3s4s5", ParseCardErrorKind::MissingSuit, 9),
+            ("As2s3x4s5s", ParseCardErrorKind::InvalidSuit('x'), 5),
+            ("As2s34s5s6", ParseCardErrorKind::InvalidSuit('4'), 5),
+            ("Asésés4s5s", ParseCardErrorKind::InvalidRank('é'), 2),
+            ("AsAé4s5s6s", ParseCardErrorKind::InvalidSuit('é'), 3),
+        ]
+        .into_iter()
+        .for_each(|(s, kind, position)| {
+            assert_eq!(
+                Hand::from_str(s),
+                Err(ParseCardError { kind, position }),
+                "{}",
+                s
+            );
+        });
+    }
+
+    #[test]
+    fn test_parse_never_panics() {
+        let alphabet = [
+            'A', 'K', 'T', '1', '2', '9', '0', 's', 'h', 'd', 'c', 'x', ' ', 'é', '♠', '🂡',
+        ];
+        let mut rng = StdRng::seed_from_u64(10);
+        for _ in 0..20_000 {
+            let len = rng.gen_range(0..14);
+            let s = (0..len)
+                .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                .collect::<String>();
+            let _ = Card::from_str(&s);
+            let _ = Suits::from_str(&s);
+            if let Ok(hand) = Hand::from_str(&s) {
+                assert_eq!(hand.to_string(), s);
+            }
+        }
+
+        // raw bytes, including invalid utf-8 replaced by lossy decoding
+        for _ in 0..5_000 {
+            let bytes = (0..rng.gen_range(0..12))
+                .map(|_| rng.gen::<u8>())
+                .collect::<Vec<_>>();
+            let s = String::from_utf8_lossy(&bytes);
+            let _ = Card::from_str(&s);
+            let _ = Hand::from_str(&s);
+        }
+    }
+
+    #[test]
+    fn test_hand_ord_hash() {
+        use std::collections::HashSet;
+
+        let a = Hand::from_str("As2s3s4s5s").unwrap();
+        let b = Hand::from_str("As2s3s4s5s").unwrap();
+        let c = Hand::from_str("Ah2s3s4s5s").unwrap();
+        assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
+        assert_ne!(a, c);
+        assert_eq!(HashSet::from([a, b, c]).len(), 2);
+    }
 }
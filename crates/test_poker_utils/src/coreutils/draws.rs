@@ -0,0 +1,311 @@
+use super::{
+    core::HandRank,
+    lookup::table,
+    predicate::{rank_bit, rank_bits, repeated_ranks, straight_high_bits},
+    Card, CardSet, Suits,
+};
+
+/// Kinds of draws, a hand can hold several at once
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Draw {
+    /// Four cards of a suit, at least one of them in the hand
+    Flush,
+    /// Four ranks in a row, completed at either end
+    OpenEnded,
+    /// Two ranks complete a straight without four in a row, e.g. 5-7-8-9-J
+    DoubleGutshot,
+    /// A single rank inside or at the end of the run completes a straight
+    Gutshot,
+    /// Three cards of a suit on the flop, needing both turn and river
+    BackdoorFlush,
+    /// Three ranks of a straight on the flop, needing both turn and river
+    BackdoorStraight,
+}
+
+/// Cards improving the hand to one category
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Outs {
+    pub rank: HandRank,
+    pub cards: Vec<Card>,
+    /// Outs leaving a paired or three-suited board, when this category is below
+    /// the full house or flush an opponent may then hold
+    pub tainted: Vec<Card>,
+}
+
+/// Draws and outs of a hole hand on a flop or turn
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DrawAnalysis {
+    /// Category made with the current cards
+    pub made: HandRank,
+    pub draws: Vec<Draw>,
+    /// Outs of each higher category, the weakest first
+    pub outs: Vec<Outs>,
+}
+
+impl DrawAnalysis {
+    pub fn has(&self, draw: Draw) -> bool {
+        self.draws.contains(&draw)
+    }
+
+    /// Every out, tainted ones included
+    pub fn all_outs(&self) -> Vec<Card> {
+        self.outs
+            .iter()
+            .flat_map(|outs| outs.cards.clone())
+            .collect()
+    }
+
+    /// Outs left once the tainted ones are discounted
+    pub fn clean_outs(&self) -> Vec<Card> {
+        self.outs
+            .iter()
+            .flat_map(|outs| {
+                outs.cards
+                    .iter()
+                    .filter(|card| !outs.tainted.contains(card))
+                    .copied()
+            })
+            .collect()
+    }
+}
+
+/// Draws and outs of two hole cards with a 3 or 4 card board
+///
+/// an out is a remaining card lifting the hand to a higher category than both
+/// the current hand and the board with that card, so pairing the board is no out.
+/// return None for other card counts or repeated cards
+pub fn analyze_draws(hole: &[Card], board: &[Card]) -> Option<DrawAnalysis> {
+    let hole_set = hole.iter().copied().collect::<CardSet>();
+    let board_set = board.iter().copied().collect::<CardSet>();
+    if hole.len() != 2 || !(3..=4).contains(&board.len()) {
+        return None;
+    }
+    if hole_set.len() != 2 || board_set.len() != board.len() || !hole_set.is_disjoint(board_set) {
+        return None;
+    }
+
+    let cards = hole_set.union(board_set);
+    let made = rank_of(cards);
+    let mut outs: Vec<Outs> = vec![];
+    for card in CardSet::FULL.difference(cards) {
+        let rank = rank_of(cards.union(card.into()));
+        let next_board = board_set.union(card.into());
+        if rank <= made || rank <= rank_of(next_board) {
+            continue;
+        }
+        let paired = next_board.nums().count_ones() as usize != next_board.len();
+        let suited = Suits::ALL
+            .iter()
+            .any(|&suit| next_board.suit_nums(suit).count_ones() >= 3);
+        let tainted = (paired && rank < HandRank::FullHouse) || (suited && rank < HandRank::Flush);
+
+        let index = match outs.iter().position(|outs| outs.rank == rank) {
+            Some(index) => index,
+            None => {
+                outs.push(Outs {
+                    rank,
+                    cards: vec![],
+                    tainted: vec![],
+                });
+                outs.len() - 1
+            }
+        };
+        outs[index].cards.push(card);
+        if tainted {
+            outs[index].tainted.push(card);
+        }
+    }
+    outs.sort_by_key(|outs| outs.rank);
+
+    Some(DrawAnalysis {
+        made,
+        draws: draws(hole_set, board_set, made),
+        outs,
+    })
+}
+
+fn draws(hole: CardSet, board: CardSet, made: HandRank) -> Vec<Draw> {
+    let cards = hole.union(board);
+    let flop = board.len() == 3;
+    let mut draws = vec![];
+
+    if made < HandRank::Flush {
+        for suit in Suits::ALL {
+            let in_hole = hole.suit_nums(suit).count_ones();
+            match (in_hole, in_hole + board.suit_nums(suit).count_ones()) {
+                (0, _) => {}
+                (_, 4) => draws.push(Draw::Flush),
+                (_, 3) if flop => draws.push(Draw::BackdoorFlush),
+                _ => {}
+            }
+        }
+    }
+
+    if made >= HandRank::Straight {
+        return draws;
+    }
+    let all = rank_bits(cards);
+    let board_bits = rank_bits(board);
+    // ranks completing a straight the board alone doesn't make
+    let completing = (2..=14)
+        .filter(|&rank| {
+            let bit = rank_bit(rank);
+            straight_high_bits(all | bit).is_some()
+                && straight_high_bits(board_bits | bit).is_none()
+        })
+        .fold(0u16, |bits, rank| bits | rank_bit(rank));
+    let open_ended = (2..=10).any(|low| {
+        let run = 0b1111 << low;
+        all & run == run && completing & (1 << (low - 1)) != 0 && completing & (1 << (low + 4)) != 0
+    });
+    match completing.count_ones() {
+        _ if open_ended => draws.push(Draw::OpenEnded),
+        0 => {}
+        1 => draws.push(Draw::Gutshot),
+        _ => draws.push(Draw::DoubleGutshot),
+    }
+    let hole_bits = rank_bits(hole);
+    let backdoor = (1..=10).any(|low| {
+        let window = 0b11111 << low;
+        (all & window).count_ones() == 3 && hole_bits & window != 0
+    });
+    if flop && completing == 0 && backdoor {
+        draws.push(Draw::BackdoorStraight);
+    }
+
+    draws
+}
+
+/// Category of the best five cards, counted from the ranks under five cards
+fn rank_of(cards: CardSet) -> HandRank {
+    if let Some(strength) = table().evaluate_set(cards) {
+        return strength.rank();
+    }
+    match repeated_ranks(cards)[..] {
+        [.., 4] => HandRank::FourOfAKind,
+        [.., 3] => HandRank::ThreeOfAKind,
+        [_, 2] => HandRank::TwoPair,
+        [2] => HandRank::OnePair,
+        _ => HandRank::HighCard,
+    }
+}
+
+#[cfg(test)]
+mod draws_tests {
+    use super::{analyze_draws, Draw};
+    use crate::{coreutils::HandRank, test_utils::cards};
+
+    #[test]
+    fn test_draws() {
+        [
+            ("AhKh", "Qh7h2c", vec![Draw::Flush, Draw::BackdoorStraight]),
+            ("AhKh", "Qh7h2c3d", vec![Draw::Flush]),
+            ("8s9d", "7c6h2s", vec![Draw::OpenEnded]),
+            ("9s8d", "6c5h2s", vec![Draw::Gutshot]),
+            ("7s8d", "5c9hJs", vec![Draw::DoubleGutshot]),
+            ("As2d", "3c4hKs", vec![Draw::Gutshot]),
+            ("JsQd", "KcAh2s", vec![Draw::Gutshot]),
+            ("9s8s", "7s6dKs", vec![Draw::Flush, Draw::OpenEnded]),
+            (
+                "Js4s",
+                "Ts8d2c",
+                vec![Draw::BackdoorFlush, Draw::BackdoorStraight],
+            ),
+            // the board's open ender isn't the hand's
+            ("AsAd", "6c7h8s9d", vec![]),
+            // made hands
+            ("9s8d", "7c6h5s", vec![]),
+            ("AhKh", "Qh7h2h", vec![]),
+        ]
+        .into_iter()
+        .for_each(|(hole, board, expect)| {
+            let analysis = analyze_draws(&cards(hole), &cards(board)).unwrap();
+            assert_eq!(analysis.draws, expect, "{} {}", hole, board);
+        });
+    }
+
+    #[test]
+    fn test_outs() {
+        let analysis = analyze_draws(&cards("AhKh"), &cards("Qh7h2c")).unwrap();
+        assert_eq!(analysis.made, HandRank::HighCard);
+        assert_eq!(analysis.outs.len(), 2);
+        // pairing an ace or a king, the queen only pairs the board
+        assert_eq!(analysis.outs[0].rank, HandRank::OnePair);
+        assert_eq!(analysis.outs[0].cards.len(), 6);
+        assert_eq!(analysis.outs[1].rank, HandRank::Flush);
+        assert_eq!(analysis.outs[1].cards.len(), 9);
+        // the deuce of hearts pairs the board
+        assert_eq!(analysis.outs[1].tainted, cards("2h"));
+        assert_eq!(analysis.clean_outs().len(), 14);
+
+        let analysis = analyze_draws(&cards("8s9d"), &cards("7c6h2s")).unwrap();
+        let straight = &analysis.outs[1];
+        assert_eq!(straight.rank, HandRank::Straight);
+        assert_eq!(straight.cards.len(), 8);
+        assert!(straight
+            .cards
+            .iter()
+            .all(|card| [5, 10].contains(&card.rank())));
+        assert_eq!(analysis.all_outs().len(), 14);
+
+        let analysis = analyze_draws(&cards("9s8d"), &cards("6c5h2s7d")).unwrap();
+        assert_eq!(analysis.made, HandRank::Straight);
+        assert!(analysis.draws.is_empty());
+        assert!(analysis.outs.is_empty());
+    }
+
+    #[test]
+    fn test_tainted_outs() {
+        // open ender and flush draw on a paired board: only the straight flush is clean
+        let analysis = analyze_draws(&cards("9h8h"), &cards("7h6h6c")).unwrap();
+        assert_eq!(analysis.made, HandRank::OnePair);
+        assert_eq!(
+            analysis
+                .outs
+                .iter()
+                .map(|outs| (outs.rank, outs.cards.len(), outs.tainted.len()))
+                .collect::<Vec<_>>(),
+            [
+                (HandRank::TwoPair, 6, 6),
+                (HandRank::Straight, 6, 6),
+                (HandRank::Flush, 7, 7),
+                (HandRank::StraightFlush, 2, 0),
+            ]
+        );
+        assert_eq!(analysis.clean_outs(), cards("5hTh"));
+
+        // the third heart on the board can give an opponent a flush
+        let analysis = analyze_draws(&cards("JsTd"), &cards("9h8h2c")).unwrap();
+        assert!(analysis.has(Draw::OpenEnded));
+        assert_eq!(analysis.all_outs().len(), 14);
+        let mut tainted = analysis
+            .outs
+            .iter()
+            .flat_map(|outs| outs.tainted.clone())
+            .collect::<Vec<_>>();
+        tainted.sort_by_key(|card| card.rank());
+        assert_eq!(tainted, cards("7hThJhQh"));
+        assert_eq!(analysis.clean_outs().len(), 10);
+    }
+
+    #[test]
+    fn test_invalid() {
+        [
+            ("Ah", "Qh7h2c"),
+            ("AhKh", "Qh7h"),
+            ("AhKh", "Qh7h2c3c4c"),
+            ("AhKh", "Qh7hKh"),
+            ("AhAh", "Qh7h2c"),
+            ("AhKh", "Qh7hQh"),
+        ]
+        .into_iter()
+        .for_each(|(hole, board)| {
+            assert_eq!(
+                analyze_draws(&cards(hole), &cards(board)),
+                None,
+                "{}",
+                board
+            );
+        });
+    }
+}
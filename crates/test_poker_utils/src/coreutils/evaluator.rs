@@ -0,0 +1,415 @@
+use std::fmt;
+
+use super::{
+    core::HandRank,
+    predicate::{is_flush, straight_high_with},
+    Card, Variant,
+};
+
+/// Evaluated strength of a five card hand
+///
+/// kickers hold the tie-breaking ranks (Ace as 14) ordered by significance,
+/// unused slots are 0. Values compare with `Ord`: the greater, the stronger.
+/// Only values evaluated under the same Variant are comparable.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct HandValue {
+    // category strength under the variant rules, see Variant::category_order
+    order: u8,
+    pub rank: HandRank,
+    pub kickers: [u8; 5],
+}
+
+impl fmt::Display for HandValue {
+    /// Showdown description, e.g. "Full house, Kings full of Sevens"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [first, second, ..] = self.kickers;
+        match self.rank {
+            HandRank::HighCard => write!(f, "High card, {}", rank_name(first)),
+            HandRank::OnePair => write!(f, "Pair of {}", rank_plural(first)),
+            HandRank::TwoPair => write!(
+                f,
+                "Two pair, {} and {}",
+                rank_plural(first),
+                rank_plural(second)
+            ),
+            HandRank::ThreeOfAKind => write!(f, "Three of a kind, {}", rank_plural(first)),
+            HandRank::FullHouse => write!(
+                f,
+                "Full house, {} full of {}",
+                rank_plural(first),
+                rank_plural(second)
+            ),
+            HandRank::FourOfAKind => write!(f, "Four of a kind, {}", rank_plural(first)),
+            HandRank::RoyalFlush => write!(f, "{}", self.rank),
+            HandRank::Straight | HandRank::Flush | HandRank::StraightFlush => {
+                write!(f, "{}, {} high", self.rank, rank_name(first))
+            }
+        }
+    }
+}
+
+pub(crate) fn rank_name(rank: u8) -> &'static str {
+    match rank {
+        2 => "Two",
+        3 => "Three",
+        4 => "Four",
+        5 => "Five",
+        6 => "Six",
+        7 => "Seven",
+        8 => "Eight",
+        9 => "Nine",
+        10 => "Ten",
+        11 => "Jack",
+        12 => "Queen",
+        13 => "King",
+        14 => "Ace",
+        _ => "Unknown",
+    }
+}
+
+pub(crate) fn rank_plural(rank: u8) -> String {
+    match rank {
+        6 => "Sixes".to_string(),
+        rank => format!("{}s", rank_name(rank)),
+    }
+}
+
+/// Evaluate exactly five cards
+///
+/// # Panics
+///
+/// Panics when `cards.len() != 5`
+pub fn evaluate(cards: &[Card]) -> HandValue {
+    evaluate_with(cards, Variant::Standard)
+}
+
+/// Evaluate exactly five cards under the variant's rules
+///
+/// # Panics
+///
+/// Panics when `cards.len() != 5`
+pub fn evaluate_with(cards: &[Card], variant: Variant) -> HandValue {
+    assert_eq!(cards.len(), 5, "evaluate expects exactly five cards");
+
+    let groups = rank_groups(cards);
+    let mut kickers = [0; 5];
+    groups
+        .iter()
+        .zip(kickers.iter_mut())
+        .for_each(|(&(_, rank), kicker)| *kicker = rank);
+
+    let flush = is_flush(cards);
+    if let Some(high) = straight_high_with(cards, variant) {
+        let rank = match (flush, high) {
+            (true, 14) => HandRank::RoyalFlush,
+            (true, _) => HandRank::StraightFlush,
+            (false, _) => HandRank::Straight,
+        };
+        return HandValue {
+            order: variant.category_order(rank),
+            rank,
+            kickers: [high, 0, 0, 0, 0],
+        };
+    }
+
+    let rank = match (groups[0].0, groups.get(1).map(|group| group.0)) {
+        (4, _) => HandRank::FourOfAKind,
+        (3, Some(2)) => HandRank::FullHouse,
+        _ if flush => HandRank::Flush,
+        (3, _) => HandRank::ThreeOfAKind,
+        (2, Some(2)) => HandRank::TwoPair,
+        (2, _) => HandRank::OnePair,
+        _ => HandRank::HighCard,
+    };
+
+    HandValue {
+        order: variant.category_order(rank),
+        rank,
+        kickers,
+    }
+}
+
+/// Best five card hand picked from a larger set of cards
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BestHand {
+    pub value: HandValue,
+    pub cards: [Card; 5],
+}
+
+/// Evaluate the best five card hand out of 5-7 cards
+///
+/// e.g. 2 hole cards + 5 board cards at a Hold'em showdown,
+/// return None when the number of cards is out of range
+pub fn evaluate_best(cards: &[Card]) -> Option<BestHand> {
+    evaluate_best_with(cards, Variant::Standard)
+}
+
+/// Evaluate the best five card hand out of 5-7 cards under the variant's rules
+pub fn evaluate_best_with(cards: &[Card], variant: Variant) -> Option<BestHand> {
+    if !(5..=7).contains(&cards.len()) {
+        return None;
+    }
+
+    let mut best: Option<BestHand> = None;
+    let mut combination = Combination::new(cards.len(), 5);
+    while let Some(indices) = combination.next_indices() {
+        let mut five = [cards[0]; 5];
+        for (card, &i) in five.iter_mut().zip(indices) {
+            *card = cards[i];
+        }
+        let value = evaluate_with(&five, variant);
+        if best.is_none_or(|best| value > best.value) {
+            best = Some(BestHand { value, cards: five });
+        }
+    }
+
+    best
+}
+
+/// Walk every k-combination of indices 0..n in lexicographic order
+pub(crate) struct Combination {
+    n: usize,
+    indices: Vec<usize>,
+    started: bool,
+}
+
+impl Combination {
+    pub(crate) fn new(n: usize, k: usize) -> Combination {
+        Combination {
+            n,
+            indices: (0..k).collect(),
+            started: false,
+        }
+    }
+
+    pub(crate) fn next_indices(&mut self) -> Option<&[usize]> {
+        let k = self.indices.len();
+        if k > self.n {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            return Some(&self.indices);
+        }
+
+        let mut i = k;
+        while i > 0 && self.indices[i - 1] == self.n - k + i - 1 {
+            i -= 1;
+        }
+        if i == 0 {
+            return None;
+        }
+        self.indices[i - 1] += 1;
+        for j in i..k {
+            self.indices[j] = self.indices[j - 1] + 1;
+        }
+
+        Some(&self.indices)
+    }
+}
+
+/// Group cards by rank as (count, rank), most frequent and highest first
+fn rank_groups(cards: &[Card]) -> Vec<(u8, u8)> {
+    let mut counts = [0u8; 15];
+    for card in cards {
+        counts[card.rank() as usize] += 1;
+    }
+
+    let mut groups = (2..=14u8)
+        .rev()
+        .filter(|&rank| counts[rank as usize] > 0)
+        .map(|rank| (counts[rank as usize], rank))
+        .collect::<Vec<_>>();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.0));
+    groups
+}
+
+#[cfg(test)]
+mod evaluator_tests {
+    use std::str::FromStr;
+
+    use super::{
+        evaluate, evaluate_best, evaluate_best_with, evaluate_with, Combination, HandValue,
+    };
+    use crate::{
+        coreutils::{
+            core::{Hand, HandRank},
+            Variant,
+        },
+        test_utils::cards,
+    };
+
+    fn eval(s: &str) -> HandValue {
+        evaluate(&Hand::from_str(s).unwrap().cards)
+    }
+
+    #[test]
+    fn test_evaluate_rank() {
+        [
+            ("TsJsQsKsAs", HandRank::RoyalFlush),
+            ("As2s3s4s5s", HandRank::StraightFlush),
+            ("9h9s9d9c2s", HandRank::FourOfAKind),
+            ("9h9s9dKcKs", HandRank::FullHouse),
+            ("2h7h9hJhKh", HandRank::Flush),
+            ("As2d3c4s5s", HandRank::Straight),
+            ("TsJdQcKsAh", HandRank::Straight),
+            ("9h9s9dKc2s", HandRank::ThreeOfAKind),
+            ("9h9sKdKc2s", HandRank::TwoPair),
+            ("9h9sQdKc2s", HandRank::OnePair),
+            ("9h8sQdKc2s", HandRank::HighCard),
+        ]
+        .into_iter()
+        .for_each(|(s, expect)| {
+            assert_eq!(eval(s).rank, expect, "{}", s);
+        });
+    }
+
+    #[test]
+    fn test_evaluate_kickers() {
+        [
+            ("As2d3c4s5s", [5, 0, 0, 0, 0]),
+            ("9h9s9dKcKs", [9, 13, 0, 0, 0]),
+            ("2h9sKdKc2s", [13, 2, 9, 0, 0]),
+            ("9h9sQdAc2s", [9, 14, 12, 2, 0]),
+            ("9h8sQdKc2s", [13, 12, 9, 8, 2]),
+        ]
+        .into_iter()
+        .for_each(|(s, expect)| {
+            assert_eq!(eval(s).kickers, expect, "{}", s);
+        });
+    }
+
+    #[test]
+    fn test_evaluate_order() {
+        // each hand is stronger than the one after it
+        let hands = [
+            "TsJsQsKsAs",
+            "9s8s7s6s5s",
+            "As2s3s4s5s",
+            "AhAsAdAcKs",
+            "AhAsAdAc2s",
+            "2h2s2d2cAs",
+            "AhAsAdKcKs",
+            "KhKsKdAcAs",
+            "AhKhQhJh9h",
+            "TsJdQcKsAh",
+            "6s2d3c4s5s",
+            "As2d3c4s5s",
+            "QhQsQdAc3s",
+            "QhQsQdAc2s",
+            "AhAsKdKc2s",
+            "AhAsQdQcKs",
+            "AhAsKdQc3s",
+            "AhAsKdQc2s",
+            "AhKsQdJc9s",
+            "7h5s4d3c2s",
+        ];
+        hands.windows(2).for_each(|pair| {
+            assert!(eval(pair[0]) > eval(pair[1]), "{} > {}", pair[0], pair[1]);
+        });
+    }
+
+    #[test]
+    fn test_evaluate_tie() {
+        assert_eq!(eval("AhKhQdJc9s"), eval("AsKdQhJs9c"));
+        assert_eq!(eval("As2d3c4s5s"), eval("Ah2h3d4c5d"));
+    }
+
+    #[test]
+    fn test_display() {
+        [
+            ("AhKhQdJc9s", "High card, Ace"),
+            ("QsQd7c4h2s", "Pair of Queens"),
+            ("AsAdKcKh2s", "Two pair, Aces and Kings"),
+            ("6s6d6cKh2s", "Three of a kind, Sixes"),
+            ("As2d3c4s5s", "Straight, Five high"),
+            ("Ks9s7s4s2s", "Flush, King high"),
+            ("KsKdKc7h7s", "Full house, Kings full of Sevens"),
+            ("TsTdTcThAs", "Four of a kind, Tens"),
+            ("5h6h7h8h9h", "Straight flush, Nine high"),
+            ("TsJsQsKsAs", "Royal flush"),
+        ]
+        .into_iter()
+        .for_each(|(s, expect)| assert_eq!(eval(s).to_string(), expect));
+    }
+
+    #[test]
+    fn test_evaluate_best() {
+        [
+            // hole cards first, then the board
+            ("AhKh", "QhJhTh2c3d", HandRank::RoyalFlush, [14, 0, 0, 0, 0]),
+            ("2c7d", "AsKsQsJsTs", HandRank::RoyalFlush, [14, 0, 0, 0, 0]),
+            (
+                "AhAd",
+                "AsAcKd2c3d",
+                HandRank::FourOfAKind,
+                [14, 13, 0, 0, 0],
+            ),
+            ("9h9d", "9sKcKd2c2d", HandRank::FullHouse, [9, 13, 0, 0, 0]),
+            ("As2h", "3d4c5h9sKd", HandRank::Straight, [5, 0, 0, 0, 0]),
+            ("As6h", "3d4c5h2sKd", HandRank::Straight, [6, 0, 0, 0, 0]),
+            ("AhKd", "QcQd7s7h2c", HandRank::TwoPair, [12, 7, 14, 0, 0]),
+            ("2c3d", "AhKsQd9h7c", HandRank::HighCard, [14, 13, 12, 9, 7]),
+        ]
+        .into_iter()
+        .for_each(|(hole, board, rank, kickers)| {
+            let cards = cards(&(hole.to_string() + board));
+            let best = evaluate_best(&cards).unwrap();
+            assert_eq!(best.value.rank, rank, "{} {}", hole, board);
+            assert_eq!(best.value.kickers, kickers, "{} {}", hole, board);
+            assert_eq!(evaluate(&best.cards), best.value);
+            assert!(best.cards.iter().all(|card| cards.contains(card)));
+        });
+    }
+
+    #[test]
+    fn test_evaluate_best_len() {
+        let eight = cards("AhKhQhJhTh2c3c4c");
+        assert!(evaluate_best(&eight[..4]).is_none());
+        assert_eq!(
+            evaluate_best(&eight[..5]).unwrap().value,
+            evaluate(&eight[..5])
+        );
+        assert!(evaluate_best(&eight).is_none());
+    }
+
+    #[test]
+    fn test_combination() {
+        let mut combination = Combination::new(7, 5);
+        let mut count = 0;
+        while let Some(indices) = combination.next_indices() {
+            assert!(indices.windows(2).all(|pair| pair[0] < pair[1]));
+            count += 1;
+        }
+        assert_eq!(count, 21);
+    }
+
+    #[test]
+    fn test_evaluate_short_deck() {
+        let short = |s: &str| evaluate_with(&Hand::from_str(s).unwrap().cards, Variant::ShortDeck);
+
+        // flush beats full house in short deck only
+        assert!(eval("9h9s9dKcKs") > eval("6h7h9hJhKh"));
+        assert!(short("6h7h9hJhKh") > short("9h9s9dKcKs"));
+        assert!(short("AhAsAdAcKs") > short("AhKhQhJh9h"));
+        assert!(short("9h9s9dKcKs") > short("AhKsQdJc9s"));
+
+        // A-6-7-8-9 is the wheel
+        let wheel = short("As6d7c8s9h");
+        assert_eq!(wheel.rank, HandRank::Straight);
+        assert_eq!(wheel.kickers, [9, 0, 0, 0, 0]);
+        assert!(short("6s7d8c9sTh") > wheel);
+        assert!(wheel > short("AhAsKdQcJs"));
+        assert_eq!(short("As6s7s8s9s").rank, HandRank::StraightFlush);
+
+        let cards = cards("As6d7c8s9hKhKd");
+        assert_eq!(
+            evaluate_best_with(&cards, Variant::ShortDeck)
+                .unwrap()
+                .value
+                .rank,
+            HandRank::Straight
+        );
+        assert_eq!(evaluate_best(&cards).unwrap().value.rank, HandRank::OnePair);
+    }
+}
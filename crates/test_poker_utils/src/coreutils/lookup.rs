@@ -0,0 +1,341 @@
+use std::sync::OnceLock;
+
+use super::{core::HandRank, evaluator::evaluate_with, Card, CardSet, HandValue, Suits, Variant};
+
+/// Prime per rank (2 to Ace), the product identifies a rank multiset
+const PRIMES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// Equivalence class of a five card hand, the greater the stronger
+///
+/// 0 never appears in an evaluation result, there are 7462 classes in total
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct HandStrength(pub u16);
+
+impl HandStrength {
+    /// Expand the class back into a full HandValue, under standard rules
+    ///
+    /// use `LookupTable::value` for strengths from another variant's table
+    pub fn value(self) -> HandValue {
+        table().values[self.0 as usize]
+    }
+
+    pub fn rank(self) -> HandRank {
+        self.value().rank
+    }
+}
+
+/// Precomputed tables for allocation free evaluation
+///
+/// flushes and uniques are indexed by the 13 bit rank mask of the hand,
+/// paired hands are found by binary search on the product of rank primes.
+pub struct LookupTable {
+    flushes: Vec<u16>,
+    uniques: Vec<u16>,
+    products: Vec<(u32, u16)>,
+    values: Vec<HandValue>,
+}
+
+/// Shared table for standard rules, built on first use
+pub fn table() -> &'static LookupTable {
+    table_for(Variant::Standard)
+}
+
+/// Shared table for the variant's rules, built on first use
+pub fn table_for(variant: Variant) -> &'static LookupTable {
+    static STANDARD: OnceLock<LookupTable> = OnceLock::new();
+    static SHORT_DECK: OnceLock<LookupTable> = OnceLock::new();
+    match variant {
+        Variant::Standard => STANDARD.get_or_init(|| LookupTable::new(variant)),
+        Variant::ShortDeck => SHORT_DECK.get_or_init(|| LookupTable::new(variant)),
+    }
+}
+
+impl LookupTable {
+    fn new(variant: Variant) -> LookupTable {
+        let card = |rank: u8, suit: Suits| Card::from(suit, if rank == 12 { 1 } else { rank + 2 });
+
+        // (is_flush, rank indices, value) for every distinct five card pattern
+        let mut patterns = vec![];
+        let mut ranks = [0u8; 5];
+        loop {
+            let mut counts = [0u8; 13];
+            ranks.iter().for_each(|&rank| counts[rank as usize] += 1);
+            if counts.iter().all(|&count| count <= 4) {
+                // rotate suits so that equal ranks never share one and no flush happens
+                let mut hand = [card(0, Suits::Spades); 5];
+                for (i, (slot, &rank)) in hand.iter_mut().zip(&ranks).enumerate() {
+                    *slot = card(rank, Suits::ALL[i % 4]);
+                }
+                patterns.push((false, ranks, evaluate_with(&hand, variant)));
+
+                if counts.iter().all(|&count| count <= 1) {
+                    let mut hand = [card(0, Suits::Spades); 5];
+                    for (slot, &rank) in hand.iter_mut().zip(&ranks) {
+                        *slot = card(rank, Suits::Spades);
+                    }
+                    patterns.push((true, ranks, evaluate_with(&hand, variant)));
+                }
+            }
+            if !next_multiset(&mut ranks) {
+                break;
+            }
+        }
+
+        let mut values = patterns.iter().map(|pattern| pattern.2).collect::<Vec<_>>();
+        values.sort();
+        values.dedup();
+        // class 0 is a placeholder for "not evaluated"
+        values.insert(0, values[0]);
+
+        let mut table = LookupTable {
+            flushes: vec![0; 1 << 13],
+            uniques: vec![0; 1 << 13],
+            products: vec![],
+            values,
+        };
+        for (flush, ranks, value) in patterns {
+            let class = table.values[1..].binary_search(&value).unwrap() as u16 + 1;
+            let mask = ranks.iter().fold(0usize, |mask, &rank| mask | 1 << rank);
+            if flush {
+                table.flushes[mask] = class;
+            } else if mask.count_ones() == 5 {
+                table.uniques[mask] = class;
+            } else {
+                let product = ranks.iter().map(|&rank| PRIMES[rank as usize]).product();
+                table.products.push((product, class));
+            }
+        }
+        table.products.sort();
+
+        table
+    }
+
+    /// Number of distinct hand classes
+    pub fn classes(&self) -> usize {
+        self.values.len() - 1
+    }
+
+    /// Expand a class of this table back into a full HandValue
+    pub fn value(&self, strength: HandStrength) -> HandValue {
+        self.values[strength.0 as usize]
+    }
+
+    /// Evaluate exactly five cards without allocating
+    ///
+    /// every card must be known (num 1-13), evaluate7 checks it for you
+    pub fn evaluate5(&self, cards: &[Card; 5]) -> HandStrength {
+        debug_assert!(cards.iter().all(|card| (1..=13).contains(&card.num)));
+        let mut mask = 0usize;
+        let mut product = 1u32;
+        for card in cards {
+            let rank = (card.rank() - 2) as usize;
+            mask |= 1 << rank;
+            product *= PRIMES[rank];
+        }
+
+        let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+        let class = if flush {
+            self.flushes[mask]
+        } else if mask.count_ones() == 5 {
+            self.uniques[mask]
+        } else {
+            match self
+                .products
+                .binary_search_by_key(&product, |entry| entry.0)
+            {
+                Ok(i) => self.products[i].1,
+                Err(_) => 0,
+            }
+        };
+
+        HandStrength(class)
+    }
+
+    /// Evaluate the best five card hand out of 5-7 cards without allocating
+    ///
+    /// return None when the number of cards is out of range or a card is unknown
+    pub fn evaluate7(&self, cards: &[Card]) -> Option<HandStrength> {
+        let n = cards.len();
+        if !(5..=7).contains(&n) || cards.iter().any(|card| !(1..=13).contains(&card.num)) {
+            return None;
+        }
+
+        let mut best = HandStrength(0);
+        for a in 0..n {
+            for b in a + 1..n {
+                for c in b + 1..n {
+                    for d in c + 1..n {
+                        for e in d + 1..n {
+                            let five = [cards[a], cards[b], cards[c], cards[d], cards[e]];
+                            best = best.max(self.evaluate5(&five));
+                        }
+                    }
+                }
+            }
+        }
+
+        Some(best)
+    }
+
+    /// Evaluate the best five card hand of a 5-7 card set without allocating
+    pub fn evaluate_set(&self, set: CardSet) -> Option<HandStrength> {
+        let mut cards = [Card::from(Suits::Spades, 0); 7];
+        let n = set.len();
+        if !(5..=7).contains(&n) {
+            return None;
+        }
+        cards
+            .iter_mut()
+            .zip(set)
+            .for_each(|(slot, card)| *slot = card);
+        self.evaluate7(&cards[..n])
+    }
+}
+
+/// Step to the next non-decreasing sequence of rank indices
+fn next_multiset(ranks: &mut [u8; 5]) -> bool {
+    let mut i = ranks.len();
+    while i > 0 && ranks[i - 1] == 12 {
+        i -= 1;
+    }
+    if i == 0 {
+        return false;
+    }
+    ranks[i - 1] += 1;
+    for j in i..ranks.len() {
+        ranks[j] = ranks[i - 1];
+    }
+    true
+}
+
+#[cfg(test)]
+mod lookup_tests {
+    use std::str::FromStr;
+
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use super::{table, table_for, HandStrength};
+    use crate::coreutils::{
+        core::{Hand, HandRank},
+        evaluate, evaluate_best, evaluate_with, Card, CardSet, Suits, Variant,
+    };
+
+    fn full_deck() -> Vec<Card> {
+        Suits::ALL
+            .into_iter()
+            .flat_map(|suit| (1..=13).map(move |num| Card::from(suit, num)))
+            .collect()
+    }
+
+    #[test]
+    fn test_classes() {
+        assert_eq!(table().classes(), 7462);
+    }
+
+    #[test]
+    fn test_evaluate5() {
+        let table = table();
+        [
+            ("TsJsQsKsAs", HandRank::RoyalFlush),
+            ("As2s3s4s5s", HandRank::StraightFlush),
+            ("9h9s9d9c2s", HandRank::FourOfAKind),
+            ("9h9s9dKcKs", HandRank::FullHouse),
+            ("2h7h9hJhKh", HandRank::Flush),
+            ("As2d3c4s5s", HandRank::Straight),
+            ("9h9s9dKc2s", HandRank::ThreeOfAKind),
+            ("9h9sKdKc2s", HandRank::TwoPair),
+            ("9h9sQdKc2s", HandRank::OnePair),
+            ("9h8sQdKc2s", HandRank::HighCard),
+        ]
+        .into_iter()
+        .for_each(|(s, expect)| {
+            let cards: [Card; 5] = Hand::from_str(s).unwrap().cards.try_into().unwrap();
+            assert_eq!(table.evaluate5(&cards).rank(), expect, "{}", s);
+        });
+        assert_eq!(table.evaluate7(&[]), None);
+        let mut unknown = Hand::from_str("AsKsQsJsTs").unwrap().cards;
+        unknown[4] = Card::from(Suits::Spades, 0);
+        assert_eq!(table.evaluate7(&unknown), None);
+    }
+
+    #[test]
+    fn test_evaluate5_exhaustive() {
+        let table = table();
+        let deck = full_deck();
+        let mut count = 0;
+        for a in 0..52 {
+            for b in a + 1..52 {
+                for c in b + 1..52 {
+                    for d in c + 1..52 {
+                        for e in d + 1..52 {
+                            let five = [deck[a], deck[b], deck[c], deck[d], deck[e]];
+                            let strength = table.evaluate5(&five);
+                            assert_ne!(strength, HandStrength(0));
+                            assert_eq!(strength.value(), evaluate(&five), "{:?}", five);
+                            count += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(count, 2_598_960);
+    }
+
+    #[test]
+    fn test_evaluate7_random() {
+        let table = table();
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut deck = full_deck();
+        for _ in 0..2000 {
+            deck.shuffle(&mut rng);
+            let seven = &deck[..7];
+            assert_eq!(
+                table.evaluate7(seven).unwrap().value(),
+                evaluate_best(seven).unwrap().value
+            );
+            assert_eq!(
+                table.evaluate_set(CardSet::from(seven)),
+                table.evaluate7(seven)
+            );
+        }
+    }
+
+    #[test]
+    fn test_short_deck_exhaustive() {
+        let variant = Variant::ShortDeck;
+        let table = table_for(variant);
+        let deck = full_deck()
+            .into_iter()
+            .filter(|card| variant.contains(card))
+            .collect::<Vec<_>>();
+        let mut count = 0;
+        for a in 0..36 {
+            for b in a + 1..36 {
+                for c in b + 1..36 {
+                    for d in c + 1..36 {
+                        for e in d + 1..36 {
+                            let five = [deck[a], deck[b], deck[c], deck[d], deck[e]];
+                            let strength = table.evaluate5(&five);
+                            assert_eq!(table.value(strength), evaluate_with(&five, variant));
+                            count += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(count, 376_992);
+
+        let flush = Hand::from_str("6h7h9hJhKh")
+            .unwrap()
+            .cards
+            .try_into()
+            .unwrap();
+        let full_house = Hand::from_str("9h9s9dKcKs")
+            .unwrap()
+            .cards
+            .try_into()
+            .unwrap();
+        assert!(table.evaluate5(&flush) > table.evaluate5(&full_house));
+        assert!(super::table().evaluate5(&flush) < super::table().evaluate5(&full_house));
+    }
+}
@@ -0,0 +1,268 @@
+use std::{cmp::Ordering, fmt};
+
+use super::{
+    core::HandRank,
+    evaluator::{evaluate, Combination},
+    Card,
+};
+
+/// Evaluated strength of a lowball hand
+///
+/// Values compare with `Ord` like HandValue: the greater, the better the low.
+/// kickers hold ranks ordered by significance, with the Ace as 1 in A-5
+/// and as 14 in 2-7. Only values of the same game are comparable.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct LowValue {
+    pub rank: HandRank,
+    pub kickers: [u8; 5],
+}
+
+impl LowValue {
+    /// Eight-or-better qualifier for A-5 split pot games: five unpaired cards of 8 or lower
+    pub fn is_eight_or_better(&self) -> bool {
+        self.rank == HandRank::HighCard && self.kickers[0] <= 8
+    }
+}
+
+impl PartialOrd for LowValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LowValue {
+    /// The lower the hand, the greater the value
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.rank, other.kickers).cmp(&(self.rank, self.kickers))
+    }
+}
+
+impl fmt::Display for LowValue {
+    /// e.g. "7-5-4-3-2", "OnePair 4-8-6-2"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranks = self
+            .kickers
+            .iter()
+            .filter(|&&rank| rank > 0)
+            .map(|&rank| match rank {
+                1 | 14 => "A".to_string(),
+                13 => "K".to_string(),
+                12 => "Q".to_string(),
+                11 => "J".to_string(),
+                10 => "T".to_string(),
+                n => n.to_string(),
+            })
+            .collect::<Vec<_>>();
+        if self.rank == HandRank::HighCard {
+            write!(f, "{}", ranks.join("-"))
+        } else {
+            write!(f, "{:?} {}", self.rank, ranks.join("-"))
+        }
+    }
+}
+
+/// Evaluate exactly five cards for A-5 lowball (Razz, Stud 8, Omaha 8)
+///
+/// Aces are low, straights and flushes don't count, 5-4-3-2-A is the best hand
+///
+/// # Panics
+///
+/// Panics when `cards.len() != 5`
+pub fn evaluate_ace_to_five(cards: &[Card]) -> LowValue {
+    assert_eq!(cards.len(), 5, "evaluate expects exactly five cards");
+
+    let mut counts = [0u8; 14];
+    cards.iter().for_each(|card| counts[card.num as usize] += 1);
+    let mut groups = (1..=13u8)
+        .rev()
+        .filter(|&num| counts[num as usize] > 0)
+        .map(|num| (counts[num as usize], num))
+        .collect::<Vec<_>>();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.0));
+
+    let mut kickers = [0; 5];
+    groups
+        .iter()
+        .zip(kickers.iter_mut())
+        .for_each(|(&(_, num), kicker)| *kicker = num);
+    let rank = match (groups[0].0, groups.get(1).map(|group| group.0)) {
+        (4, _) => HandRank::FourOfAKind,
+        (3, Some(2)) => HandRank::FullHouse,
+        (3, _) => HandRank::ThreeOfAKind,
+        (2, Some(2)) => HandRank::TwoPair,
+        (2, _) => HandRank::OnePair,
+        _ => HandRank::HighCard,
+    };
+
+    LowValue { rank, kickers }
+}
+
+/// Evaluate exactly five cards for 2-7 lowball (Triple Draw, Single Draw)
+///
+/// Aces are high, straights and flushes count against the hand and
+/// A-2-3-4-5 is not a straight, 7-5-4-3-2 is the best hand
+///
+/// # Panics
+///
+/// Panics when `cards.len() != 5`
+pub fn evaluate_deuce_to_seven(cards: &[Card]) -> LowValue {
+    let value = evaluate(cards);
+    match (value.rank, value.kickers[0]) {
+        // the wheel is only ace high
+        (HandRank::Straight, 5) => LowValue {
+            rank: HandRank::HighCard,
+            kickers: [14, 5, 4, 3, 2],
+        },
+        (HandRank::StraightFlush, 5) => LowValue {
+            rank: HandRank::Flush,
+            kickers: [14, 5, 4, 3, 2],
+        },
+        (rank, _) => LowValue {
+            rank,
+            kickers: value.kickers,
+        },
+    }
+}
+
+/// Best A-5 low out of 5-7 cards, e.g. seven cards in Razz
+pub fn best_ace_to_five(cards: &[Card]) -> Option<LowValue> {
+    best_low(cards, evaluate_ace_to_five)
+}
+
+/// Best 2-7 low out of 5-7 cards
+pub fn best_deuce_to_seven(cards: &[Card]) -> Option<LowValue> {
+    best_low(cards, evaluate_deuce_to_seven)
+}
+
+fn best_low(cards: &[Card], evaluate: fn(&[Card]) -> LowValue) -> Option<LowValue> {
+    if !(5..=7).contains(&cards.len()) {
+        return None;
+    }
+
+    let mut best: Option<LowValue> = None;
+    let mut combination = Combination::new(cards.len(), 5);
+    while let Some(indices) = combination.next_indices() {
+        let mut five = [cards[0]; 5];
+        for (card, &i) in five.iter_mut().zip(indices) {
+            *card = cards[i];
+        }
+        let value = evaluate(&five);
+        if best.is_none_or(|best| value > best) {
+            best = Some(value);
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod lowball_tests {
+    use std::str::FromStr;
+
+    use super::{
+        best_ace_to_five, best_deuce_to_seven, evaluate_ace_to_five, evaluate_deuce_to_seven,
+        LowValue,
+    };
+    use crate::coreutils::{core::HandRank, Card, Hand};
+
+    fn ace_to_five(s: &str) -> LowValue {
+        evaluate_ace_to_five(&Hand::from_str(s).unwrap().cards)
+    }
+
+    fn deuce_to_seven(s: &str) -> LowValue {
+        evaluate_deuce_to_seven(&Hand::from_str(s).unwrap().cards)
+    }
+
+    #[test]
+    fn test_ace_to_five_order() {
+        // each hand is a better low than the one after it
+        let hands = [
+            "5s4d3c2hAs",
+            "6s4d3c2hAs",
+            "6s5d4c3h2s",
+            "7s4d3c2hAs",
+            "8s7d6c5h4s",
+            "9s4d3c2hAs",
+            "KsQdJcTh9s",
+            "AsAd2c3h4s",
+            "2s2d3c4h5s",
+            "KsKdQcJhTs",
+            "AsAd2c2h3s",
+            "AsAdAc2h3s",
+            "AsAdAc2h2s",
+            "AsAdAcAh2s",
+        ];
+        hands.windows(2).for_each(|pair| {
+            assert!(
+                ace_to_five(pair[0]) > ace_to_five(pair[1]),
+                "{} > {}",
+                pair[0],
+                pair[1]
+            );
+        });
+        assert_eq!(ace_to_five("5s4d3c2hAs").to_string(), "5-4-3-2-A");
+        // suits never matter
+        assert_eq!(ace_to_five("5s4d3c2hAs"), ace_to_five("5h4h3h2hAh"));
+    }
+
+    #[test]
+    fn test_eight_or_better() {
+        [
+            ("8s7d6c5h4s", true),
+            ("5s4d3c2hAs", true),
+            ("9s4d3c2hAs", false),
+            ("AsAd2c3h4s", false),
+        ]
+        .into_iter()
+        .for_each(|(s, expect)| {
+            assert_eq!(ace_to_five(s).is_eight_or_better(), expect, "{}", s);
+        });
+    }
+
+    #[test]
+    fn test_deuce_to_seven_order() {
+        let hands = [
+            "7s5d4c3h2s",
+            "7s6d4c3h2s",
+            "7s6d5c4h2s",
+            "8s5d4c3h2s",
+            "Ks5d4c3h2s",
+            "As5d4c3h2s",
+            "AsKdQcJh9s",
+            "2s2d3c4h5s",
+            "AsAdKcQhJs",
+            "6s5d4c3h2s",
+            "AsKdQcJhTs",
+            "7s5s4s3s2s",
+            "2s2d2c3h3s",
+        ];
+        hands.windows(2).for_each(|pair| {
+            assert!(
+                deuce_to_seven(pair[0]) > deuce_to_seven(pair[1]),
+                "{} > {}",
+                pair[0],
+                pair[1]
+            );
+        });
+        assert_eq!(deuce_to_seven("As5d4c3h2s").rank, HandRank::HighCard);
+        assert_eq!(deuce_to_seven("As5s4s3s2s").rank, HandRank::Flush);
+        assert_eq!(deuce_to_seven("7s5d4c3h2s").to_string(), "7-5-4-3-2");
+    }
+
+    #[test]
+    fn test_best_low() {
+        // razz: seven cards, the pair and the king don't play
+        let cards = Card::from_strs(vec!["Ks", "2d", "2c", "5h", "7s", "3d", "Ac"]).unwrap();
+        let best = best_ace_to_five(&cards).unwrap();
+        assert_eq!(best.kickers, [7, 5, 3, 2, 1]);
+        assert!(best.is_eight_or_better());
+
+        // 2-7: the ace and the 8 stay out of the nuts
+        let cards = Card::from_strs(vec!["As", "2d", "3c", "4h", "5s", "7d", "8c"]).unwrap();
+        assert_eq!(
+            best_deuce_to_seven(&cards).unwrap().kickers,
+            [7, 5, 4, 3, 2]
+        );
+        assert!(best_ace_to_five(&cards[..4]).is_none());
+    }
+}
@@ -1,5 +1,35 @@
+pub mod card_set;
+pub use card_set::CardSet;
+
 pub mod core;
-pub use core::{Card, CardDeck, Suits};
+pub use core::{
+    Card, CardDeck, DeckError, Hand, HandRank, ParseCardError, ParseCardErrorKind, Suits,
+};
+
+pub mod draws;
+pub use draws::{analyze_draws, Draw, DrawAnalysis, Outs};
+
+pub mod evaluator;
+pub use evaluator::{
+    evaluate, evaluate_best, evaluate_best_with, evaluate_with, BestHand, HandValue,
+};
+
+pub mod lookup;
+pub use lookup::HandStrength;
+
+pub mod lowball;
+pub use lowball::{
+    best_ace_to_five, best_deuce_to_seven, evaluate_ace_to_five, evaluate_deuce_to_seven, LowValue,
+};
+
+pub mod omaha;
+pub use omaha::{evaluate_omaha, evaluate_omaha_low};
 
 pub mod predicate;
 pub use predicate::is_flush;
+
+pub mod texture;
+pub use texture::{BoardTexture, HighCardClass, Pairing, Suitedness};
+
+pub mod variant;
+pub use variant::Variant;
@@ -0,0 +1,191 @@
+use super::{
+    evaluator::{evaluate, BestHand, Combination},
+    lowball::{evaluate_ace_to_five, LowValue},
+    Card,
+};
+
+/// Evaluate the best Omaha hand (PLO4, PLO5 or PLO6)
+///
+/// a hand must use exactly two of the 4-6 hole cards and three of the 3-5 board cards,
+/// return None when the number of cards is out of range
+pub fn evaluate_omaha(hole: &[Card], board: &[Card]) -> Option<BestHand> {
+    best_two_plus_three(hole, board, |five| Some(evaluate(five)))
+        .map(|(value, cards)| BestHand { value, cards })
+}
+
+/// Evaluate the best eight-or-better low of an Omaha Hi/Lo hand
+///
+/// the low follows the same two plus three rule as the high,
+/// return None when no combination qualifies or the number of cards is out of range
+pub fn evaluate_omaha_low(hole: &[Card], board: &[Card]) -> Option<LowValue> {
+    best_two_plus_three(hole, board, |five| {
+        Some(evaluate_ace_to_five(five)).filter(LowValue::is_eight_or_better)
+    })
+    .map(|(value, _)| value)
+}
+
+/// Best value over every two hole cards plus three board cards combination
+fn best_two_plus_three<T: Ord>(
+    hole: &[Card],
+    board: &[Card],
+    evaluate: impl Fn(&[Card]) -> Option<T>,
+) -> Option<(T, [Card; 5])> {
+    if !(4..=6).contains(&hole.len()) || !(3..=5).contains(&board.len()) {
+        return None;
+    }
+
+    let mut best: Option<(T, [Card; 5])> = None;
+    let mut hole_pick = Combination::new(hole.len(), 2);
+    while let Some(hole_indices) = hole_pick.next_indices() {
+        let (a, b) = (hole[hole_indices[0]], hole[hole_indices[1]]);
+        let mut board_pick = Combination::new(board.len(), 3);
+        while let Some(board_indices) = board_pick.next_indices() {
+            let five = [
+                a,
+                b,
+                board[board_indices[0]],
+                board[board_indices[1]],
+                board[board_indices[2]],
+            ];
+            let Some(value) = evaluate(&five) else {
+                continue;
+            };
+            if best.as_ref().is_none_or(|(best, _)| value > *best) {
+                best = Some((value, five));
+            }
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod omaha_tests {
+    use super::{evaluate_omaha, evaluate_omaha_low};
+    use crate::{coreutils::core::HandRank, test_utils::cards};
+
+    #[test]
+    fn test_evaluate_omaha() {
+        [
+            // four hearts on board, one heart in hand: no flush
+            (
+                "Ah9s8s7d",
+                "KhQhJh2h3c",
+                HandRank::HighCard,
+                [14, 13, 12, 11, 9],
+            ),
+            (
+                "Th9s8s7d",
+                "AhKhQhJh2c",
+                HandRank::Straight,
+                [13, 0, 0, 0, 0],
+            ),
+            // two hearts in hand complete it
+            (
+                "Ah9h8s7d",
+                "KhQhJh2h3c",
+                HandRank::Flush,
+                [14, 13, 12, 11, 9],
+            ),
+            // quads in hand only play as a pair
+            ("AsAhAdAc", "Kd7c2s", HandRank::OnePair, [14, 13, 7, 2, 0]),
+            // four to a straight on board needs two hole cards
+            (
+                "Kc3d4h5s",
+                "9dTcJhQs2c",
+                HandRank::HighCard,
+                [13, 12, 11, 10, 5],
+            ),
+            (
+                "KcAd4h5s",
+                "9dTcJhQs2c",
+                HandRank::Straight,
+                [14, 0, 0, 0, 0],
+            ),
+            // trips on board pair with a pocket pair for the full house
+            (
+                "AsAh3d4c",
+                "7s7h7dKc2d",
+                HandRank::FullHouse,
+                [7, 14, 0, 0, 0],
+            ),
+            (
+                "Ks3h4d5c",
+                "7s7h7dKc2d",
+                HandRank::ThreeOfAKind,
+                [7, 13, 5, 0, 0],
+            ),
+            // board pair + one pocket card is only trips
+            (
+                "7c3h4d5c",
+                "7s7hKdQc2d",
+                HandRank::ThreeOfAKind,
+                [7, 13, 5, 0, 0],
+            ),
+        ]
+        .into_iter()
+        .for_each(|(hole, board, rank, kickers)| {
+            let (hole_cards, board_cards) = (cards(hole), cards(board));
+            let best = evaluate_omaha(&hole_cards, &board_cards).unwrap();
+            assert_eq!(best.value.rank, rank, "{} {}", hole, board);
+            assert_eq!(best.value.kickers, kickers, "{} {}", hole, board);
+            assert_eq!(
+                best.cards
+                    .iter()
+                    .filter(|card| hole_cards.contains(card))
+                    .count(),
+                2
+            );
+            assert_eq!(
+                best.cards
+                    .iter()
+                    .filter(|card| board_cards.contains(card))
+                    .count(),
+                3
+            );
+        });
+    }
+
+    #[test]
+    fn test_evaluate_omaha_sizes() {
+        // PLO5 and PLO6 find the two suited cards anywhere in the hand
+        let board = cards("KhQhJh2h3c");
+        let best = evaluate_omaha(&cards("2s9s8s7dAh9h"), &board).unwrap();
+        assert_eq!(best.value.rank, HandRank::Flush);
+        assert_eq!(
+            evaluate_omaha(&cards("2s9s8sAh9h"), &board)
+                .unwrap()
+                .value
+                .rank,
+            HandRank::Flush
+        );
+
+        assert!(evaluate_omaha(&cards("AhKh9s"), &board).is_none());
+        assert!(evaluate_omaha(&cards("AhKh9s8s7s6s5s"), &board).is_none());
+        assert!(evaluate_omaha(&cards("AhKh9s8s"), &board[..2]).is_none());
+        assert!(evaluate_omaha(&cards("As9s8s7s"), &board[..3]).is_some());
+    }
+
+    #[test]
+    fn test_evaluate_omaha_low() {
+        [
+            // the best low uses A2 from the hand, not the 3 and 4
+            ("As2d3c4h", "5s8d6cKhQs", Some([8, 6, 5, 2, 1])),
+            ("As2d3c4h", "5s6d7cKhQs", Some([7, 6, 5, 2, 1])),
+            // a hand paired with the board still plays its other cards
+            ("AsAd3cKh", "2s5d8cKdQs", Some([8, 5, 3, 2, 1])),
+            // only two low cards on board: no low
+            ("As2d3c4h", "5s8dTcKhQs", None),
+            // only one low card in hand: no low
+            ("AsKdQcJh", "2s3d4c5h6s", None),
+            // counterfeited wheel cards leave a pair
+            ("As2dKcKh", "Ac2c3c9hTs", None),
+        ]
+        .into_iter()
+        .for_each(|(hole, board, kickers)| {
+            let low = evaluate_omaha_low(&cards(hole), &cards(board));
+            assert_eq!(low.map(|low| low.kickers), kickers, "{} {}", hole, board);
+        });
+        assert!(evaluate_omaha_low(&cards("As2d3c"), &cards("4s5d6c")).is_none());
+    }
+}
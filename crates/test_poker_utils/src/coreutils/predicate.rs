@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use super::Card;
+use super::{Card, CardSet, Variant};
 
 /// Flush predication
 ///
@@ -30,6 +30,7 @@ pub fn is_flush(cards: &[Card]) -> bool {
 #[test]
 fn test_is_flush() {
     use crate::coreutils::core::Hand;
+    use std::str::FromStr;
     [
         ("As2s3s4s5s", true),
         ("Ah2h3h4h5h", true),
@@ -45,22 +46,44 @@ fn test_is_flush() {
 ///
 /// straight: five card's number are continous
 pub fn is_straight(cards: &[Card]) -> bool {
+    straight_high(cards).is_some()
+}
+
+/// Top rank of a straight (Ace as 14, 5 for the A-2-3-4-5 wheel)
+///
+/// return None when cards are not a five card straight
+pub fn straight_high(cards: &[Card]) -> Option<u8> {
+    straight_high_with(cards, Variant::Standard)
+}
+
+/// Top rank of a straight under the variant's wheel (e.g. 9 for A-6-7-8-9 in short deck)
+pub fn straight_high_with(cards: &[Card], variant: Variant) -> Option<u8> {
     if cards.len() != 5 {
-        return false;
+        return None;
     }
 
-    let mut nums = cards.iter().map(|card| card.num).collect::<Vec<_>>();
-    nums.sort();
-    for i in (0..nums.len()).rev() {
-        nums[i] -= nums[0];
+    let mut ranks = cards.iter().map(|card| card.rank()).collect::<Vec<_>>();
+    ranks.sort();
+    let wheel_high = variant.wheel_high();
+    let wheel = (wheel_high - 3..=wheel_high)
+        .chain([14])
+        .collect::<Vec<_>>();
+    if ranks.eq(&wheel) {
+        return Some(wheel_high);
+    }
+    for i in 1..ranks.len() {
+        if ranks[i] != ranks[i - 1] + 1 {
+            return None;
+        }
     }
 
-    nums.eq(&vec![0, 1, 2, 3, 4]) || nums.eq(&vec![0, 9, 10, 11, 12])
+    Some(ranks[4])
 }
 
 #[test]
 fn test_is_straight() {
     use crate::coreutils::core::Hand;
+    use std::str::FromStr;
     [
         ("As2d3c4s5s", true),
         ("4s5d6c7s8s", true),
@@ -76,6 +99,116 @@ fn test_is_straight() {
     });
 }
 
+#[test]
+fn test_straight_high() {
+    use crate::coreutils::core::Hand;
+    use std::str::FromStr;
+    [
+        ("As2d3c4s5s", Some(5)),
+        ("4s5d6c7s8s", Some(8)),
+        ("TsJsQsKsAs", Some(14)),
+        ("JsQdKcAs2s", None),
+        ("3s2d4c6s5s", Some(6)),
+        ("3s3d4c6s5s", None),
+    ]
+    .into_iter()
+    .for_each(|(s, expect)| {
+        assert_eq!(straight_high(&Hand::from_str(s).unwrap().cards), expect);
+    });
+}
+
+#[test]
+fn test_straight_high_with() {
+    use crate::coreutils::core::Hand;
+    use std::str::FromStr;
+    [
+        ("As6d7c8s9s", Variant::Standard, None),
+        ("As6d7c8s9s", Variant::ShortDeck, Some(9)),
+        ("As2d3c4s5s", Variant::ShortDeck, None),
+        ("TsJdQcKsAs", Variant::ShortDeck, Some(14)),
+        ("6s7d8c9sTs", Variant::ShortDeck, Some(10)),
+    ]
+    .into_iter()
+    .for_each(|(s, variant, expect)| {
+        let cards = Hand::from_str(s).unwrap().cards;
+        assert_eq!(straight_high_with(&cards, variant), expect, "{}", s);
+    });
+}
+
+/// One bit per rank 2-14 of the cards, the Ace also sets bit 1 for the wheel
+pub fn rank_bits(cards: CardSet) -> u16 {
+    cards
+        .iter()
+        .fold(0, |bits, card| bits | rank_bit(card.rank()))
+}
+
+/// Bit of a rank 2-14 in a rank_bits mask
+pub fn rank_bit(rank: u8) -> u16 {
+    let bit = 1 << rank;
+    if rank == 14 {
+        bit | 0b10
+    } else {
+        bit
+    }
+}
+
+/// Top rank of the best straight in a rank_bits mask, from any number of cards
+pub fn straight_high_bits(bits: u16) -> Option<u8> {
+    (5..=14)
+        .rev()
+        .find(|&high| bits >> (high - 4) & 0b11111 == 0b11111)
+}
+
+#[test]
+fn test_straight_high_bits() {
+    [
+        ("As2d3c4s5s", Some(5)),
+        ("As2d3c4s5s6h", Some(6)),
+        ("TsJsQsKsAs9d", Some(14)),
+        ("JsQdKcAs2s", None),
+        ("3s3d4c6s5s", None),
+        ("3s3d4c6s5s7d2h", Some(7)),
+    ]
+    .into_iter()
+    .for_each(|(s, expect)| {
+        let cards = Card::parse_many(s).unwrap();
+        let bits = rank_bits(CardSet::from(cards.as_slice()));
+        assert_eq!(straight_high_bits(bits), expect, "{}", s);
+    });
+}
+
+/// How often each repeated rank of the cards appears, the fewest first
+///
+/// e.g. [2, 3] for a full house, empty without a pair
+pub fn repeated_ranks(cards: CardSet) -> Vec<usize> {
+    let mut counts = (1..=13)
+        .map(|num| cards.intersection(CardSet::rank_mask(num)).len())
+        .filter(|&count| count > 1)
+        .collect::<Vec<_>>();
+    counts.sort_unstable();
+    counts
+}
+
+#[test]
+fn test_repeated_ranks() {
+    [
+        ("AsKdQc", vec![]),
+        ("AsAdQc", vec![2]),
+        ("AsAdQcQs2d", vec![2, 2]),
+        ("AsAdAc2s2d2c2h", vec![3, 4]),
+    ]
+    .into_iter()
+    .for_each(|(s, expect)| {
+        let cards = Card::parse_many(s).unwrap();
+        assert_eq!(
+            repeated_ranks(CardSet::from(cards.as_slice())),
+            expect,
+            "{}",
+            s
+        );
+    });
+}
+
 /// Straight Flush predication
 pub fn is_straight_flush(cards: &[Card]) -> bool {
     is_flush(cards) && is_straight(cards)
@@ -84,6 +217,7 @@ pub fn is_straight_flush(cards: &[Card]) -> bool {
 #[test]
 fn test_is_straight_flush() {
     use crate::coreutils::core::Hand;
+    use std::str::FromStr;
     [
         ("As2s3s4s5s", true),
         ("6s2s3s4s5s", true),
@@ -114,6 +248,7 @@ pub fn is_royal_flush(cards: &[Card]) -> bool {
 #[test]
 fn test_is_royal_flush() {
     use crate::coreutils::core::Hand;
+    use std::str::FromStr;
     [
         ("TsJsQsKsAs", true),
         ("ThJhQhKhAh", true),
@@ -156,6 +291,7 @@ fn get_num_count(cards: &Vec<Card>) -> HashMap<u8, u8> {
 #[test]
 fn test_is_foru_of_a_kind() {
     use crate::coreutils::core::Hand;
+    use std::str::FromStr;
     [
         ("AsAsAsAs2s", true),
         ("5s5s5s5s2s", true),
@@ -0,0 +1,406 @@
+use super::{
+    lookup::table,
+    predicate::{rank_bits, repeated_ranks, straight_high_bits},
+    Card, CardSet, HandRank, HandStrength, Suits,
+};
+
+/// How the ranks of the board repeat
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Pairing {
+    Unpaired,
+    Paired,
+    TwoPair,
+    Trips,
+    FullHouse,
+    Quads,
+}
+
+/// How the suits of the board spread
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Suitedness {
+    /// No suit twice
+    Rainbow,
+    /// A suit twice at most
+    TwoTone,
+    /// Three or four cards of a suit among others, on the turn or river
+    ThreeSuited,
+    /// Every card of one suit
+    Monotone,
+}
+
+/// Class of the highest board card
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum HighCardClass {
+    /// Eight or lower
+    Low,
+    /// Nine to jack
+    Middle,
+    /// Queen or king
+    High,
+    Ace,
+}
+
+/// Texture of a flop, turn or river
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BoardTexture {
+    pub pairing: Pairing,
+    pub suits: Suitedness,
+    /// Suit with three or more cards, making a flush possible
+    pub flush_suit: Option<Suits>,
+    /// Most distinct ranks fitting in one straight, three or more make one possible
+    pub connected: u8,
+    pub high_card: HighCardClass,
+    /// Best hand two hole cards can make
+    pub nuts: HandStrength,
+    // one bit per high rank of a possible straight
+    straights: u16,
+    board: CardSet,
+    // the board without its last card, None on the flop
+    previous: Option<CardSet>,
+}
+
+/// Hole cards by rank and whether each is of the flush suit
+type Holding = (u8, bool, u8, bool);
+
+impl BoardTexture {
+    /// Texture of 3 to 5 board cards in the order they were dealt
+    ///
+    /// return None for other card counts or repeated cards
+    pub fn of(board: &[Card]) -> Option<BoardTexture> {
+        let set = board.iter().copied().collect::<CardSet>();
+        if !(3..=5).contains(&board.len()) || set.len() != board.len() {
+            return None;
+        }
+
+        let pairing = match repeated_ranks(set)[..] {
+            [.., 4] => Pairing::Quads,
+            [2, 3] => Pairing::FullHouse,
+            [.., 3] => Pairing::Trips,
+            [_, 2] => Pairing::TwoPair,
+            [2] => Pairing::Paired,
+            _ => Pairing::Unpaired,
+        };
+
+        let (most, suit) = Suits::ALL
+            .iter()
+            .map(|&suit| (set.suit_nums(suit).count_ones(), suit))
+            .max()
+            .unwrap();
+        let suits = match most as usize {
+            1 => Suitedness::Rainbow,
+            2 => Suitedness::TwoTone,
+            most if most == board.len() => Suitedness::Monotone,
+            _ => Suitedness::ThreeSuited,
+        };
+        let flush_suit = (most >= 3).then_some(suit);
+
+        let bits = rank_bits(set);
+        let connected = (5..=14)
+            .map(|high| window(bits, high).count_ones())
+            .max()
+            .unwrap_or(0) as u8;
+        let straights = straight_highs(bits);
+
+        let high_card = match board.iter().map(|card| card.rank()).max().unwrap_or(0) {
+            14 => HighCardClass::Ace,
+            12..=13 => HighCardClass::High,
+            9..=11 => HighCardClass::Middle,
+            _ => HighCardClass::Low,
+        };
+
+        let previous =
+            (board.len() > 3).then(|| board[..board.len() - 1].iter().copied().collect());
+
+        Some(BoardTexture {
+            pairing,
+            suits,
+            flush_suit,
+            connected,
+            high_card,
+            nuts: nuts(set, None),
+            straights,
+            board: set,
+            previous,
+        })
+    }
+
+    /// No holding which was the nuts on the previous street still is, false on the flop
+    ///
+    /// the previous street is the board without its last card. This searches the nuts
+    /// of both streets again, so it is left out of `of`
+    pub fn nuts_changed(&self) -> bool {
+        let Some(previous) = self.previous else {
+            return false;
+        };
+        let mut holdings = vec![];
+        let mut before = vec![];
+        nuts(self.board, Some(&mut holdings));
+        nuts(previous, Some(&mut before));
+        !before.iter().any(|holding| holdings.contains(holding))
+    }
+
+    pub fn is_paired(&self) -> bool {
+        self.pairing != Pairing::Unpaired
+    }
+
+    pub fn flush_possible(&self) -> bool {
+        self.flush_suit.is_some()
+    }
+
+    pub fn straight_possible(&self) -> bool {
+        self.straights != 0
+    }
+
+    /// High ranks of the possible straights, the highest first
+    pub fn straights(&self) -> Vec<u8> {
+        (5..=14)
+            .rev()
+            .filter(|high| self.straights & 1 << high != 0)
+            .collect()
+    }
+}
+
+/// Ranks high to high - 4, the Ace low at bit 1
+fn window(bits: u16, high: u8) -> u16 {
+    bits >> (high - 4) & 0b11111
+}
+
+/// One bit per high rank of a straight two more ranks can complete
+fn straight_highs(bits: u16) -> u16 {
+    (5..=14)
+        .filter(|&high| window(bits, high).count_ones() >= 3)
+        .fold(0, |highs, high| highs | 1 << high)
+}
+
+/// Best strength two hole cards reach on the board, every holding reaching it is
+/// pushed to holdings when given
+///
+/// the best category is found from the board first, so only hole cards making it
+/// are evaluated. Cards off the flush suit can't make a flush, one card of each rank
+/// stands for all of them
+fn nuts(board: CardSet, mut holdings: Option<&mut Vec<Holding>>) -> HandStrength {
+    let flush = Suits::ALL
+        .into_iter()
+        .find(|&suit| board.suit_nums(suit).count_ones() >= 3)
+        .map_or(CardSet::EMPTY, CardSet::suit_mask);
+    let paired = board.nums().count_ones() as usize != board.len();
+    let target = if straight_highs(rank_bits(board.intersection(flush))) != 0 {
+        HandRank::StraightFlush
+    } else if paired {
+        HandRank::FourOfAKind
+    } else if !flush.is_empty() {
+        HandRank::Flush
+    } else if straight_highs(rank_bits(board)) != 0 {
+        HandRank::Straight
+    } else {
+        HandRank::ThreeOfAKind
+    };
+    let reaches = |hand: CardSet| match target {
+        HandRank::StraightFlush => {
+            straight_high_bits(rank_bits(hand.intersection(flush))).is_some()
+        }
+        HandRank::Flush => hand.intersection(flush).len() >= 5,
+        HandRank::Straight => straight_high_bits(rank_bits(hand)).is_some(),
+        _ => {
+            let count = if paired { 4 } else { 3 };
+            (1..=13).any(|num| hand.intersection(CardSet::rank_mask(num)).len() >= count)
+        }
+    };
+
+    let rest = CardSet::FULL.difference(board);
+    // the card of the flush suit then two of the other suits left of each rank
+    let mut ranks = [[None; 3]; 13];
+    for (num, options) in (1..=13).zip(ranks.iter_mut()) {
+        let cards = rest.intersection(CardSet::rank_mask(num));
+        options[0] = cards.intersection(flush).iter().next();
+        let mut plain = cards.difference(flush).iter();
+        options[1] = plain.next();
+        options[2] = plain.next();
+    }
+
+    let mut best = HandStrength(0);
+    for a in 0..13 {
+        for b in a..13 {
+            // a pocket pair is two plain cards or the suited one with a plain one
+            let pairs: &[(usize, usize)] = if a == b {
+                &[(1, 2), (0, 1)]
+            } else {
+                &[(0, 0), (0, 1), (1, 0), (1, 1)]
+            };
+            for &(i, j) in pairs {
+                let (Some(x), Some(y)) = (ranks[a][i], ranks[b][j]) else {
+                    continue;
+                };
+                let hand = board.union(x.into()).union(y.into());
+                if !reaches(hand) {
+                    continue;
+                }
+                // boards of 3-5 cards make 5-7 with the hole cards
+                let strength = table().evaluate_set(hand).unwrap();
+                if strength < best {
+                    continue;
+                }
+                if let Some(holdings) = holdings.as_mut() {
+                    if strength > best {
+                        holdings.clear();
+                    }
+                    holdings.push((x.num, i == 0, y.num, j == 0));
+                }
+                best = strength;
+            }
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod texture_tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{BoardTexture, HighCardClass, Pairing, Suitedness};
+    use crate::{
+        coreutils::{lookup::table, CardDeck, CardSet, HandRank, HandStrength, Suits},
+        test_utils::cards,
+    };
+
+    fn texture(board: &str) -> BoardTexture {
+        BoardTexture::of(&cards(board)).unwrap()
+    }
+
+    #[test]
+    fn test_pairing() {
+        [
+            ("Ah7c2d", Pairing::Unpaired),
+            ("7s7d2c", Pairing::Paired),
+            ("7s7d2c2h", Pairing::TwoPair),
+            ("7s7d7c", Pairing::Trips),
+            ("7s7d7c2h2d", Pairing::FullHouse),
+            ("7s2d2c7c7h", Pairing::FullHouse),
+            ("7s7d7c7h", Pairing::Quads),
+        ]
+        .into_iter()
+        .for_each(|(board, expect)| {
+            assert_eq!(texture(board).pairing, expect, "{}", board);
+        });
+    }
+
+    #[test]
+    fn test_suits() {
+        [
+            ("Ah7c2d", Suitedness::Rainbow, None),
+            ("Ah7c2d3s", Suitedness::Rainbow, None),
+            ("Ah7h2d", Suitedness::TwoTone, None),
+            ("Ah7h2c3c9d", Suitedness::TwoTone, None),
+            ("Ah7h2h3d", Suitedness::ThreeSuited, Some(Suits::Hearts)),
+            ("Ah7h2h3h9d", Suitedness::ThreeSuited, Some(Suits::Hearts)),
+            ("Ah7h2h", Suitedness::Monotone, Some(Suits::Hearts)),
+            ("Ac7c2c3c9c", Suitedness::Monotone, Some(Suits::Clubs)),
+        ]
+        .into_iter()
+        .for_each(|(board, suits, flush_suit)| {
+            let texture = texture(board);
+            assert_eq!(texture.suits, suits, "{}", board);
+            assert_eq!(texture.flush_suit, flush_suit, "{}", board);
+            assert_eq!(texture.flush_possible(), flush_suit.is_some(), "{}", board);
+        });
+    }
+
+    #[test]
+    fn test_straights() {
+        [
+            ("Kh7c2d", 1, vec![]),
+            ("Ah7c2d", 2, vec![]),
+            ("Ah2c3d", 3, vec![5]),
+            ("JsTd9c", 3, vec![13, 12, 11]),
+            ("JsTd8c", 3, vec![12, 11]),
+            ("AsKdQc", 3, vec![14]),
+            ("5s6d7c8h9s", 5, vec![11, 10, 9, 8, 7]),
+        ]
+        .into_iter()
+        .for_each(|(board, connected, straights)| {
+            let texture = texture(board);
+            assert_eq!(texture.connected, connected, "{}", board);
+            assert_eq!(texture.straight_possible(), !straights.is_empty());
+            assert_eq!(texture.straights(), straights, "{}", board);
+        });
+    }
+
+    #[test]
+    fn test_high_card() {
+        [
+            ("2s5d8c", HighCardClass::Low),
+            ("9s5d2c", HighCardClass::Middle),
+            ("Js5d2c", HighCardClass::Middle),
+            ("Ks5d2c", HighCardClass::High),
+            ("As5d2c", HighCardClass::Ace),
+        ]
+        .into_iter()
+        .for_each(|(board, expect)| {
+            assert_eq!(texture(board).high_card, expect, "{}", board);
+        });
+    }
+
+    #[test]
+    fn test_nuts() {
+        [
+            ("Ah7c2d", HandRank::ThreeOfAKind, false),
+            ("7s7d2c", HandRank::FourOfAKind, false),
+            ("9h8h2h", HandRank::Flush, false),
+            ("JsTd9c", HandRank::Straight, false),
+            // top set stays the nuts
+            ("Ah7c2dKc", HandRank::ThreeOfAKind, false),
+            // a higher set
+            ("Qh7c2dKs", HandRank::ThreeOfAKind, true),
+            // the wheel
+            ("Ah7c2d3h", HandRank::Straight, true),
+            // the ace-king of hearts is still the nut flush
+            ("9h8h2h3c", HandRank::Flush, false),
+            ("9h8h2hTh", HandRank::StraightFlush, true),
+            // the third heart brings a flush, the pair then quads
+            ("Ah7h2d3c9h", HandRank::Flush, true),
+            ("Ah7h2d3c7s", HandRank::FourOfAKind, true),
+        ]
+        .into_iter()
+        .for_each(|(board, rank, changed)| {
+            let texture = texture(board);
+            assert_eq!(texture.nuts.rank(), rank, "{}", board);
+            assert_eq!(texture.nuts_changed(), changed, "{}", board);
+        });
+    }
+
+    #[test]
+    fn test_nuts_random() {
+        let mut deck = CardDeck::with_rng(StdRng::seed_from_u64(3));
+        for _ in 0..50 {
+            deck.reset();
+            let board = deck.deal_n(5).unwrap();
+            for n in 3..=5 {
+                let set = board[..n].iter().copied().collect::<CardSet>();
+                let rest = CardSet::FULL.difference(set).to_vec();
+                let mut nuts = HandStrength(0);
+                for (i, &x) in rest.iter().enumerate() {
+                    for &y in &rest[i + 1..] {
+                        let hand = set.union(x.into()).union(y.into());
+                        nuts = nuts.max(table().evaluate_set(hand).unwrap());
+                    }
+                }
+                assert_eq!(
+                    BoardTexture::of(&board[..n]).unwrap().nuts,
+                    nuts,
+                    "{:?}",
+                    &board[..n]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        ["Ah7c", "Ah7c2d3s4h5c", "Ah7cAh"]
+            .into_iter()
+            .for_each(|board| {
+                assert_eq!(BoardTexture::of(&cards(board)), None, "{}", board);
+            });
+    }
+}
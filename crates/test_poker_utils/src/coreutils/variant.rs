@@ -0,0 +1,84 @@
+use super::{Card, HandRank};
+
+/// Deck and ranking rules of a Hold'em variant
+///
+/// ShortDeck (6+) plays with 36 cards (no 2-5), a flush beats a full house
+/// and A-6-7-8-9 is the lowest straight.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum Variant {
+    #[default]
+    Standard,
+    ShortDeck,
+}
+
+impl Variant {
+    /// Whether the card belongs to this variant's deck
+    pub fn contains(self, card: &Card) -> bool {
+        match self {
+            Variant::Standard => (1..=13).contains(&card.num),
+            Variant::ShortDeck => card.num == 1 || (6..=13).contains(&card.num),
+        }
+    }
+
+    pub fn deck_size(self) -> usize {
+        match self {
+            Variant::Standard => 52,
+            Variant::ShortDeck => 36,
+        }
+    }
+
+    /// Top rank of the lowest straight, the one where the Ace plays low
+    pub fn wheel_high(self) -> u8 {
+        match self {
+            Variant::Standard => 5,
+            Variant::ShortDeck => 9,
+        }
+    }
+
+    /// Strength of a category under this variant, compared before anything else
+    pub fn category_order(self, rank: HandRank) -> u8 {
+        match (self, rank) {
+            (Variant::ShortDeck, HandRank::Flush) => HandRank::FullHouse as u8,
+            (Variant::ShortDeck, HandRank::FullHouse) => HandRank::Flush as u8,
+            (_, rank) => rank as u8,
+        }
+    }
+}
+
+#[cfg(test)]
+mod variant_tests {
+    use super::Variant;
+    use crate::coreutils::{Card, HandRank, Suits};
+
+    #[test]
+    fn test_contains() {
+        [Variant::Standard, Variant::ShortDeck]
+            .into_iter()
+            .for_each(|variant| {
+                let count = (0..52)
+                    .filter_map(Card::from_index)
+                    .filter(|card| variant.contains(card))
+                    .count();
+                assert_eq!(count, variant.deck_size());
+            });
+        assert!(!Variant::ShortDeck.contains(&Card::from(Suits::Spades, 5)));
+        assert!(Variant::ShortDeck.contains(&Card::from(Suits::Spades, 1)));
+        assert!(!Variant::Standard.contains(&Card::from(Suits::Spades, 0)));
+    }
+
+    #[test]
+    fn test_category_order() {
+        let standard = Variant::Standard;
+        let short = Variant::ShortDeck;
+        assert!(
+            standard.category_order(HandRank::FullHouse) > standard.category_order(HandRank::Flush)
+        );
+        assert!(short.category_order(HandRank::Flush) > short.category_order(HandRank::FullHouse));
+        assert!(
+            short.category_order(HandRank::FourOfAKind) > short.category_order(HandRank::Flush)
+        );
+        assert!(
+            short.category_order(HandRank::FullHouse) > short.category_order(HandRank::Straight)
+        );
+    }
+}
@@ -0,0 +1,901 @@
+use std::fmt;
+
+use rand::Rng;
+
+use crate::{
+    coreutils::{
+        evaluator::Combination, lookup, Card, CardSet, DeckError, HandStrength, Suits, Variant,
+    },
+    range::{Combo, Range},
+};
+
+/// Errors when setting up an equity calculation
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EquityError {
+    /// At least two hands are needed
+    NotEnoughPlayers,
+    /// A board has at most 5 cards
+    TooManyBoardCards(usize),
+    /// Not enough unseen cards left to complete the board
+    NotEnoughCards,
+    /// A card is duplicated across hands, board and dead cards, or invalid
+    Deck(DeckError),
+    /// The range at this index has no combo left once board and dead cards are removed
+    EmptyRange(usize),
+    /// The ranges never give a set of combos without shared cards
+    NoCompatibleCombos,
+}
+
+impl fmt::Display for EquityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EquityError::NotEnoughPlayers => write!(f, "at least two hands are needed"),
+            EquityError::TooManyBoardCards(n) => write!(f, "board has {} cards, at most 5", n),
+            EquityError::NotEnoughCards => write!(f, "not enough cards left to complete the board"),
+            EquityError::Deck(err) => write!(f, "{}", err),
+            EquityError::EmptyRange(i) => write!(f, "range {} has no combo left", i),
+            EquityError::NoCompatibleCombos => write!(f, "ranges have no compatible combos"),
+        }
+    }
+}
+
+impl std::error::Error for EquityError {}
+
+impl From<DeckError> for EquityError {
+    fn from(err: DeckError) -> Self {
+        EquityError::Deck(err)
+    }
+}
+
+/// Outcome of one hand over all runouts
+///
+/// share counts the pots won, in `1 / unit` of a pot, so split pots stay exact
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PlayerEquity {
+    pub wins: u64,
+    pub ties: u64,
+    pub losses: u64,
+    pub share: u64,
+    pub unit: u64,
+    share_squares: u128,
+}
+
+impl PlayerEquity {
+    fn new(unit: u64) -> PlayerEquity {
+        PlayerEquity {
+            wins: 0,
+            ties: 0,
+            losses: 0,
+            share: 0,
+            unit,
+            share_squares: 0,
+        }
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.wins + self.ties + self.losses
+    }
+
+    pub fn win_pct(&self) -> f64 {
+        self.pct(self.wins)
+    }
+
+    pub fn tie_pct(&self) -> f64 {
+        self.pct(self.ties)
+    }
+
+    pub fn loss_pct(&self) -> f64 {
+        self.pct(self.losses)
+    }
+
+    /// Share of the pot won on average, ties split between winners, in percent
+    pub fn equity_pct(&self) -> f64 {
+        self.mean() * 100.0
+    }
+
+    /// Equity as an exact fraction (numerator, denominator)
+    pub fn equity_fraction(&self) -> (u64, u64) {
+        (self.share, self.samples() * self.unit)
+    }
+
+    /// Normal approximation interval of equity_pct, z = 1.96 for 95%
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        let n = self.samples() as f64;
+        if n == 0.0 {
+            return (0.0, 100.0);
+        }
+        let mean = self.mean();
+        let unit = self.unit as f64;
+        let variance = (self.share_squares as f64 / (unit * unit) / n - mean * mean).max(0.0);
+        let margin = z * (variance / n).sqrt();
+
+        (
+            ((mean - margin) * 100.0).max(0.0),
+            ((mean + margin) * 100.0).min(100.0),
+        )
+    }
+
+    fn mean(&self) -> f64 {
+        let (numerator, denominator) = self.equity_fraction();
+        if denominator == 0 {
+            return 0.0;
+        }
+        numerator as f64 / denominator as f64
+    }
+
+    fn pct(&self, count: u64) -> f64 {
+        let samples = self.samples();
+        if samples == 0 {
+            return 0.0;
+        }
+        count as f64 * 100.0 / samples as f64
+    }
+
+    fn record(&mut self, winners: u64, won: bool) {
+        if !won {
+            self.losses += 1;
+            return;
+        }
+        if winners == 1 {
+            self.wins += 1;
+        } else {
+            self.ties += 1;
+        }
+        let share = self.unit / winners;
+        self.share += share;
+        self.share_squares += (share as u128) * (share as u128);
+    }
+}
+
+/// Result of an equity calculation, one entry per hand in input order
+///
+/// exact is set when every runout was enumerated instead of sampled
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EquityReport {
+    pub samples: u64,
+    pub exact: bool,
+    pub players: Vec<PlayerEquity>,
+}
+
+impl EquityReport {
+    fn new(players: usize, exact: bool) -> EquityReport {
+        // divisible by any number of winners
+        let unit = (1..=players as u64).fold(1, |unit, n| unit / gcd(unit, n) * n);
+        EquityReport {
+            samples: 0,
+            exact,
+            players: (0..players).map(|_| PlayerEquity::new(unit)).collect(),
+        }
+    }
+
+    fn record(&mut self, strengths: &[HandStrength]) {
+        let best = strengths.iter().max().copied();
+        let winners = strengths.iter().filter(|&&s| Some(s) == best).count() as u64;
+        self.samples += 1;
+        for (player, &strength) in self.players.iter_mut().zip(strengths) {
+            player.record(winners, Some(strength) == best);
+        }
+    }
+}
+
+/// Hold'em equity between known hole cards
+///
+/// ```
+/// use rand::{rngs::StdRng, SeedableRng};
+/// use test_poker_utils::{coreutils::Card, equity::EquityCalculator};
+///
+/// let aces = Card::from_strs(vec!["Ah", "Ad"]).unwrap();
+/// let kings = Card::from_strs(vec!["Ks", "Kc"]).unwrap();
+/// let report = EquityCalculator::new(vec![[aces[0], aces[1]], [kings[0], kings[1]]])
+///     .monte_carlo(1000, StdRng::seed_from_u64(0))
+///     .unwrap();
+/// assert!(report.players[0].equity_pct() > 70.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EquityCalculator {
+    hands: Vec<[Card; 2]>,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    variant: Variant,
+    enumeration_limit: u64,
+}
+
+/// Default number of runouts under which `calculate` enumerates them all
+pub const DEFAULT_ENUMERATION_LIMIT: u64 = 100_000;
+
+impl EquityCalculator {
+    pub fn new(hands: Vec<[Card; 2]>) -> EquityCalculator {
+        EquityCalculator {
+            hands,
+            board: vec![],
+            dead: vec![],
+            variant: Variant::Standard,
+            enumeration_limit: DEFAULT_ENUMERATION_LIMIT,
+        }
+    }
+
+    /// Known board cards (0-5)
+    pub fn board(mut self, board: &[Card]) -> EquityCalculator {
+        self.board = board.to_vec();
+        self
+    }
+
+    /// Cards known to be out of the deck, e.g. folded hands
+    pub fn dead(mut self, dead: &[Card]) -> EquityCalculator {
+        self.dead = dead.to_vec();
+        self
+    }
+
+    /// Deck and hand rankings to play with, standard by default
+    pub fn variant(mut self, variant: Variant) -> EquityCalculator {
+        self.variant = variant;
+        self
+    }
+
+    /// Max number of runouts `calculate` still enumerates exactly
+    pub fn enumeration_limit(mut self, limit: u64) -> EquityCalculator {
+        self.enumeration_limit = limit;
+        self
+    }
+
+    /// Number of distinct runouts over the unseen cards
+    pub fn runouts(&self) -> Result<u64, EquityError> {
+        let known = self.validate()?;
+        Ok(binomial(
+            (self.variant.deck_size() - known.len()) as u64,
+            5 - self.board.len() as u64,
+        ))
+    }
+
+    /// Enumerate when the runouts are within the enumeration limit,
+    /// sample `iterations` random runouts otherwise
+    pub fn calculate<R: Rng>(&self, iterations: u64, rng: R) -> Result<EquityReport, EquityError> {
+        if self.runouts()? <= self.enumeration_limit {
+            self.enumerate()
+        } else {
+            self.monte_carlo(iterations, rng)
+        }
+    }
+
+    /// Walk every runout over the unseen cards for exact win/tie counts
+    pub fn enumerate(&self) -> Result<EquityReport, EquityError> {
+        let known = self.validate()?;
+        let missing = 5 - self.board.len();
+        let unseen = (deck_of(self.variant) - known).to_vec();
+
+        let mut board = [Card::from(Suits::Spades, 0); 5];
+        board[..self.board.len()].copy_from_slice(&self.board);
+
+        let mut report = EquityReport::new(self.hands.len(), true);
+        let mut combination = Combination::new(unseen.len(), missing);
+        while let Some(indices) = combination.next_indices() {
+            for (card, &i) in board[5 - missing..].iter_mut().zip(indices) {
+                *card = unseen[i];
+            }
+            self.record(&board, &mut report);
+        }
+
+        Ok(report)
+    }
+
+    /// Sample random runouts, the same rng seed gives the same report
+    pub fn monte_carlo<R: Rng>(
+        &self,
+        iterations: u64,
+        mut rng: R,
+    ) -> Result<EquityReport, EquityError> {
+        let known = self.validate()?;
+        let missing = 5 - self.board.len();
+        let mut unseen = (deck_of(self.variant) - known).to_vec();
+        let mut board = [Card::from(Suits::Spades, 0); 5];
+        board[..self.board.len()].copy_from_slice(&self.board);
+
+        let mut report = EquityReport::new(self.hands.len(), false);
+        for _ in 0..iterations {
+            deal_runout(
+                &mut unseen,
+                CardSet::EMPTY,
+                &mut board[5 - missing..],
+                &mut rng,
+            );
+            self.record(&board, &mut report);
+        }
+
+        Ok(report)
+    }
+
+    fn record(&self, board: &[Card; 5], report: &mut EquityReport) {
+        let table = lookup::table_for(self.variant);
+        let mut strengths = Vec::with_capacity(self.hands.len());
+        for hand in &self.hands {
+            let seven = [
+                hand[0], hand[1], board[0], board[1], board[2], board[3], board[4],
+            ];
+            strengths.push(table.evaluate7(&seven).unwrap());
+        }
+        report.record(&strengths);
+    }
+
+    /// Check the setup and collect every known card
+    fn validate(&self) -> Result<CardSet, EquityError> {
+        if self.hands.len() < 2 {
+            return Err(EquityError::NotEnoughPlayers);
+        }
+        if self.board.len() > 5 {
+            return Err(EquityError::TooManyBoardCards(self.board.len()));
+        }
+
+        let known = known_cards(
+            self.hands
+                .iter()
+                .flatten()
+                .chain(&self.board)
+                .chain(&self.dead),
+            self.variant,
+        )?;
+        if known.len() + 5 - self.board.len() > self.variant.deck_size() {
+            return Err(EquityError::NotEnoughCards);
+        }
+
+        Ok(known)
+    }
+}
+
+/// Equity of one range, overall and per combo
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RangeResult {
+    pub equity: PlayerEquity,
+    /// Every combo left after card removal, with its own outcome when dealt
+    pub combos: Vec<(Combo, PlayerEquity)>,
+}
+
+/// Result of a range vs range calculation, one entry per range in input order
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RangeEquityReport {
+    pub samples: u64,
+    pub ranges: Vec<RangeResult>,
+}
+
+/// Hold'em equity between weighted ranges
+///
+/// Each sample draws one combo per range by weight and rejects the draw when
+/// two ranges hold the same card, then deals a random runout.
+#[derive(Debug, Clone)]
+pub struct RangeEquityCalculator {
+    ranges: Vec<Range>,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    variant: Variant,
+}
+
+/// Rejected draws in a row before giving up on the ranges
+const MAX_REJECTIONS: u32 = 10_000;
+
+impl RangeEquityCalculator {
+    pub fn new(ranges: Vec<Range>) -> RangeEquityCalculator {
+        RangeEquityCalculator {
+            ranges,
+            board: vec![],
+            dead: vec![],
+            variant: Variant::Standard,
+        }
+    }
+
+    /// Known board cards (0-5)
+    pub fn board(mut self, board: &[Card]) -> RangeEquityCalculator {
+        self.board = board.to_vec();
+        self
+    }
+
+    /// Cards known to be out of the deck
+    pub fn dead(mut self, dead: &[Card]) -> RangeEquityCalculator {
+        self.dead = dead.to_vec();
+        self
+    }
+
+    /// Deck and hand rankings to play with, combos outside the deck are dropped
+    pub fn variant(mut self, variant: Variant) -> RangeEquityCalculator {
+        self.variant = variant;
+        self
+    }
+
+    /// Sample `iterations` deals, the same rng seed gives the same report
+    pub fn monte_carlo<R: Rng>(
+        &self,
+        iterations: u64,
+        mut rng: R,
+    ) -> Result<RangeEquityReport, EquityError> {
+        let ranges = self.validate()?;
+        let missing = 5 - self.board.len();
+        let known = CardSet::from(self.board.as_slice()) | CardSet::from(self.dead.as_slice());
+        let mut unseen = (deck_of(self.variant) - known).to_vec();
+
+        // cumulative weights per range to draw combos from
+        let totals = ranges
+            .iter()
+            .map(|range| {
+                range
+                    .combos()
+                    .iter()
+                    .scan(0.0, |total, (_, weight)| {
+                        *total += weight;
+                        Some(*total)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let summary = EquityReport::new(ranges.len(), false);
+        let mut report = RangeEquityReport {
+            samples: 0,
+            ranges: ranges
+                .iter()
+                .zip(&summary.players)
+                .map(|(range, player)| RangeResult {
+                    equity: player.clone(),
+                    combos: range
+                        .combos()
+                        .iter()
+                        .map(|(combo, _)| (*combo, player.clone()))
+                        .collect(),
+                })
+                .collect(),
+        };
+
+        let mut picks = vec![0; ranges.len()];
+        let mut board = [Card::from(Suits::Spades, 0); 5];
+        board[..self.board.len()].copy_from_slice(&self.board);
+        let mut strengths = vec![HandStrength(0); ranges.len()];
+        for _ in 0..iterations {
+            let mut rejections = 0;
+            while !draw_combos(&ranges, &totals, &mut picks, &mut rng) {
+                rejections += 1;
+                if rejections >= MAX_REJECTIONS {
+                    return Err(EquityError::NoCompatibleCombos);
+                }
+            }
+
+            let picked = ranges
+                .iter()
+                .zip(&picks)
+                .flat_map(|(range, &pick)| range.combos()[pick].0.cards())
+                .collect::<CardSet>();
+            deal_runout(&mut unseen, picked, &mut board[5 - missing..], &mut rng);
+
+            let table = lookup::table_for(self.variant);
+            for ((range, &pick), strength) in ranges.iter().zip(&picks).zip(strengths.iter_mut()) {
+                let [a, b] = range.combos()[pick].0.cards();
+                let seven = [a, b, board[0], board[1], board[2], board[3], board[4]];
+                *strength = table.evaluate7(&seven).unwrap();
+            }
+            let best = strengths.iter().max().copied();
+            let winners = strengths.iter().filter(|&&s| Some(s) == best).count() as u64;
+
+            report.samples += 1;
+            for ((result, &pick), &strength) in report.ranges.iter_mut().zip(&picks).zip(&strengths)
+            {
+                let won = Some(strength) == best;
+                result.equity.record(winners, won);
+                result.combos[pick].1.record(winners, won);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Check the setup and drop combos that hit the board or dead cards
+    fn validate(&self) -> Result<Vec<Range>, EquityError> {
+        if self.ranges.len() < 2 {
+            return Err(EquityError::NotEnoughPlayers);
+        }
+        if self.board.len() > 5 {
+            return Err(EquityError::TooManyBoardCards(self.board.len()));
+        }
+        let known = known_cards(self.board.iter().chain(&self.dead), self.variant)?;
+        if known.len() + 2 * self.ranges.len() + 5 - self.board.len() > self.variant.deck_size() {
+            return Err(EquityError::NotEnoughCards);
+        }
+        let known = (known | !deck_of(self.variant)).to_vec();
+
+        self.ranges
+            .iter()
+            .enumerate()
+            .map(|(i, range)| {
+                let range = range.without(&known);
+                if range.is_empty() {
+                    Err(EquityError::EmptyRange(i))
+                } else {
+                    Ok(range)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Draw one combo per range by weight, false when two of them share a card
+fn draw_combos<R: Rng>(
+    ranges: &[Range],
+    totals: &[Vec<f64>],
+    picks: &mut [usize],
+    rng: &mut R,
+) -> bool {
+    for i in 0..ranges.len() {
+        let cumulative = &totals[i];
+        let target = rng.gen::<f64>() * cumulative[cumulative.len() - 1];
+        let pick = cumulative
+            .partition_point(|&total| total <= target)
+            .min(cumulative.len() - 1);
+        let combo = &ranges[i].combos()[pick].0;
+        if (0..i).any(|j| ranges[j].combos()[picks[j]].0.conflicts(combo)) {
+            return false;
+        }
+        picks[i] = pick;
+    }
+    true
+}
+
+/// Every card of the variant's deck
+fn deck_of(variant: Variant) -> CardSet {
+    CardSet::FULL
+        .iter()
+        .filter(|card| variant.contains(card))
+        .collect()
+}
+
+/// Known cards as a set, a card out of the variant's deck or seen twice is an error
+fn known_cards<'a>(
+    cards: impl IntoIterator<Item = &'a Card>,
+    variant: Variant,
+) -> Result<CardSet, EquityError> {
+    let mut known = CardSet::new();
+    for &card in cards {
+        if !variant.contains(&card) {
+            return Err(DeckError::InvalidCard(card).into());
+        }
+        if known.contains(card) {
+            return Err(DeckError::AlreadyDealt(card).into());
+        }
+        known.insert(card);
+    }
+    Ok(known)
+}
+
+/// Fill runout with random cards from unseen, skipping the excluded ones
+///
+/// a partial Fisher-Yates shuffle: only the front of unseen is reordered, and
+/// unseen must hold enough cards outside excluded
+fn deal_runout<R: Rng>(unseen: &mut [Card], excluded: CardSet, runout: &mut [Card], rng: &mut R) {
+    let mut i = 0;
+    for card in runout.iter_mut() {
+        loop {
+            let j = rng.gen_range(i..unseen.len());
+            unseen.swap(i, j);
+            i += 1;
+            if !excluded.contains(unseen[i - 1]) {
+                *card = unseen[i - 1];
+                break;
+            }
+        }
+    }
+}
+
+fn binomial(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod equity_tests {
+    use std::str::FromStr;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{EquityCalculator, EquityError, RangeEquityCalculator};
+    use crate::{
+        coreutils::{Card, DeckError, Variant},
+        range::Range,
+    };
+
+    fn hand(a: &str, b: &str) -> [Card; 2] {
+        let cards = Card::from_strs(vec![a, b]).unwrap();
+        [cards[0], cards[1]]
+    }
+
+    #[test]
+    fn test_monte_carlo_preflop() {
+        let report = EquityCalculator::new(vec![hand("Ah", "Ad"), hand("Ks", "Kc")])
+            .monte_carlo(20_000, StdRng::seed_from_u64(6))
+            .unwrap();
+
+        // AA vs KK is about 82% / 18%
+        let aces = &report.players[0];
+        let (low, high) = aces.confidence_interval(3.0);
+        assert!(low < 82.0 && 82.0 < high, "{} {}", low, high);
+        assert_eq!(aces.samples(), 20_000);
+        assert_eq!(report.samples, 20_000);
+
+        let total = report.players[0].equity_pct() + report.players[1].equity_pct();
+        assert!((total - 100.0).abs() < 1e-9);
+        assert!((aces.win_pct() + aces.tie_pct() + aces.loss_pct() - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_monte_carlo_seeded() {
+        let calculator =
+            EquityCalculator::new(vec![hand("Ah", "Kh"), hand("Qs", "Qc"), hand("7d", "8d")])
+                .board(&Card::from_strs(vec!["2h", "9d", "Td"]).unwrap());
+
+        let first = calculator
+            .monte_carlo(2000, StdRng::seed_from_u64(9))
+            .unwrap();
+        let second = calculator
+            .monte_carlo(2000, StdRng::seed_from_u64(9))
+            .unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_monte_carlo_river() {
+        // board plays: always a split pot
+        let report = EquityCalculator::new(vec![hand("2c", "3d"), hand("4c", "2d")])
+            .board(&Card::from_strs(vec!["Ah", "Kh", "Qh", "Jh", "Th"]).unwrap())
+            .monte_carlo(10, StdRng::seed_from_u64(0))
+            .unwrap();
+        report.players.iter().for_each(|player| {
+            assert_eq!(player.ties, 10);
+            assert_eq!(player.equity_fraction(), (10, 20));
+            assert_eq!(player.equity_pct(), 50.0);
+        });
+    }
+
+    #[test]
+    fn test_monte_carlo_dead() {
+        // both remaining hearts are dead, the flush draw can't come
+        let report = EquityCalculator::new(vec![hand("Ah", "2h"), hand("Ks", "Kc")])
+            .board(&Card::from_strs(vec!["3h", "4h", "Td", "9c"]).unwrap())
+            .dead(
+                &Card::from_strs(vec![
+                    "5h", "Qh", "Jh", "8h", "7h", "6h", "Kh", "Th", "5d", "5c", "5s",
+                ])
+                .unwrap(),
+            )
+            .monte_carlo(500, StdRng::seed_from_u64(0))
+            .unwrap();
+        assert!(report.players[0].equity_pct() < 20.0);
+    }
+
+    #[test]
+    fn test_enumerate_turn() {
+        // only the two remaining aces save AA against a set of kings
+        let calculator = EquityCalculator::new(vec![hand("Ac", "Ad"), hand("Ks", "Kd")])
+            .board(&Card::from_strs(vec!["2s", "7h", "9d", "Kc"]).unwrap());
+        assert_eq!(calculator.runouts(), Ok(44));
+
+        let report = calculator.enumerate().unwrap();
+        assert!(report.exact);
+        assert_eq!(report.samples, 44);
+        assert_eq!(
+            (
+                report.players[0].wins,
+                report.players[0].ties,
+                report.players[0].losses
+            ),
+            (2, 0, 42)
+        );
+        assert_eq!(report.players[0].equity_fraction(), (4, 88));
+        assert_eq!(report.players[1].wins, 42);
+    }
+
+    #[test]
+    fn test_enumerate_flop() {
+        let calculator =
+            EquityCalculator::new(vec![hand("Ah", "Kh"), hand("Qs", "Qc"), hand("Qh", "Jd")])
+                .board(&Card::from_strs(vec!["2h", "9h", "Td"]).unwrap());
+        let report = calculator.enumerate().unwrap();
+        assert_eq!(report.samples, 903);
+
+        // every split is counted exactly
+        let total = report
+            .players
+            .iter()
+            .map(|player| player.equity_fraction().0)
+            .sum::<u64>();
+        assert_eq!(total, 903 * report.players[0].unit);
+
+        let sampled = calculator
+            .monte_carlo(5000, StdRng::seed_from_u64(7))
+            .unwrap();
+        report
+            .players
+            .iter()
+            .zip(&sampled.players)
+            .for_each(|(exact, sampled)| {
+                let (low, high) = sampled.confidence_interval(4.0);
+                assert!(low <= exact.equity_pct() && exact.equity_pct() <= high);
+            });
+    }
+
+    #[test]
+    fn test_calculate_mode() {
+        let calculator = EquityCalculator::new(vec![hand("Ah", "Kh"), hand("Qs", "Qc")])
+            .board(&Card::from_strs(vec!["2h", "9h", "Td"]).unwrap());
+        assert_eq!(calculator.runouts(), Ok(990));
+        assert!(
+            calculator
+                .calculate(100, StdRng::seed_from_u64(0))
+                .unwrap()
+                .exact
+        );
+
+        let calculator = calculator.enumeration_limit(989);
+        let report = calculator.calculate(100, StdRng::seed_from_u64(0)).unwrap();
+        assert!(!report.exact);
+        assert_eq!(report.samples, 100);
+
+        // preflop is far above the default limit
+        let preflop = EquityCalculator::new(vec![hand("Ah", "Kh"), hand("Qs", "Qc")]);
+        assert_eq!(preflop.runouts(), Ok(1_712_304));
+        assert!(
+            !preflop
+                .calculate(10, StdRng::seed_from_u64(0))
+                .unwrap()
+                .exact
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let aces = hand("Ah", "Ad");
+        assert_eq!(
+            EquityCalculator::new(vec![aces])
+                .monte_carlo(1, StdRng::seed_from_u64(0))
+                .unwrap_err(),
+            EquityError::NotEnoughPlayers
+        );
+        assert_eq!(
+            EquityCalculator::new(vec![aces, hand("Ah", "Kd")])
+                .monte_carlo(1, StdRng::seed_from_u64(0))
+                .unwrap_err(),
+            EquityError::Deck(DeckError::AlreadyDealt(aces[0]))
+        );
+        assert_eq!(
+            EquityCalculator::new(vec![aces, hand("Ks", "Kd")])
+                .board(&Card::from_strs(vec!["2c", "3c", "4c", "5c", "6c", "7c"]).unwrap())
+                .monte_carlo(1, StdRng::seed_from_u64(0))
+                .unwrap_err(),
+            EquityError::TooManyBoardCards(6)
+        );
+    }
+
+    #[test]
+    fn test_range_equity() {
+        let ranges = vec![
+            Range::from_str("AA").unwrap(),
+            Range::from_str("KK").unwrap(),
+        ];
+        let report = RangeEquityCalculator::new(ranges)
+            .monte_carlo(10_000, StdRng::seed_from_u64(11))
+            .unwrap();
+        assert_eq!(report.samples, 10_000);
+
+        let (low, high) = report.ranges[0].equity.confidence_interval(3.0);
+        assert!(low < 82.0 && 82.0 < high, "{} {}", low, high);
+
+        // every combo gets dealt, and the breakdown adds up to the total
+        let aces = &report.ranges[0];
+        assert_eq!(aces.combos.len(), 6);
+        assert!(aces.combos.iter().all(|(_, equity)| equity.samples() > 0));
+        let samples = aces
+            .combos
+            .iter()
+            .map(|(_, equity)| equity.samples())
+            .sum::<u64>();
+        assert_eq!(samples, 10_000);
+    }
+
+    #[test]
+    fn test_range_equity_conflicts() {
+        // the board takes Kh, the first range takes Ah and Ad
+        let ranges = vec![
+            Range::from_str("AhAd").unwrap(),
+            Range::from_str("AA, KK").unwrap(),
+        ];
+        let board = Card::from_strs(vec!["Kh", "7c", "2d"]).unwrap();
+        let report = RangeEquityCalculator::new(ranges)
+            .board(&board)
+            .monte_carlo(2000, StdRng::seed_from_u64(12))
+            .unwrap();
+
+        let second = &report.ranges[1];
+        assert_eq!(second.combos.len(), 9);
+        second.combos.iter().for_each(|(combo, equity)| {
+            assert!(!combo.contains(&board[0]));
+            let blocked = combo.contains(&Card::from_strs(vec!["Ah"]).unwrap()[0])
+                || combo.contains(&Card::from_strs(vec!["Ad"]).unwrap()[0]);
+            assert_eq!(equity.samples() == 0, blocked, "{}", combo);
+        });
+
+        let first = RangeEquityCalculator::new(vec![
+            Range::from_str("AhAd").unwrap(),
+            Range::from_str("AA, KK").unwrap(),
+        ])
+        .board(&board)
+        .monte_carlo(2000, StdRng::seed_from_u64(12))
+        .unwrap();
+        assert_eq!(first, report);
+    }
+
+    #[test]
+    fn test_range_equity_errors() {
+        let board = Card::from_strs(vec!["Ah", "Ad", "2c"]).unwrap();
+        assert_eq!(
+            RangeEquityCalculator::new(vec![
+                Range::from_str("KK").unwrap(),
+                Range::from_str("AhAd").unwrap(),
+            ])
+            .board(&board)
+            .monte_carlo(1, StdRng::seed_from_u64(0))
+            .unwrap_err(),
+            EquityError::EmptyRange(1)
+        );
+        assert_eq!(
+            RangeEquityCalculator::new(vec![
+                Range::from_str("AsKs").unwrap(),
+                Range::from_str("AsQs").unwrap(),
+            ])
+            .monte_carlo(1, StdRng::seed_from_u64(0))
+            .unwrap_err(),
+            EquityError::NoCompatibleCombos
+        );
+    }
+
+    #[test]
+    fn test_short_deck() {
+        // A-6-7-8-9 is already a straight against a set of aces in short deck,
+        // a standard deck needs a 5 or a T on the river
+        let calculator = EquityCalculator::new(vec![hand("7h", "8d"), hand("Ac", "Ad")])
+            .board(&Card::from_strs(vec!["As", "6c", "9h", "Kd"]).unwrap());
+        let standard = calculator.clone().enumerate().unwrap();
+        let short = calculator.variant(Variant::ShortDeck).enumerate().unwrap();
+        assert_eq!((standard.samples, standard.players[0].wins), (44, 8));
+        // aces fill up on any A, K, 9 or 6
+        assert_eq!(short.samples, 36 - 8);
+        assert_eq!((short.players[0].wins, short.players[0].losses), (18, 10));
+
+        // flush over full house turns the river around
+        let calculator = EquityCalculator::new(vec![hand("Kh", "Th"), hand("9s", "9c")])
+            .board(&Card::from_strs(vec!["9h", "7h", "6h", "Kd", "Ks"]).unwrap());
+        let standard = calculator.clone().enumerate().unwrap();
+        let short = calculator.variant(Variant::ShortDeck).enumerate().unwrap();
+        assert_eq!(standard.players[1].wins, 1);
+        assert_eq!(short.players[0].wins, 1);
+
+        assert_eq!(
+            EquityCalculator::new(vec![hand("2h", "8d"), hand("Ac", "Ad")])
+                .variant(Variant::ShortDeck)
+                .enumerate()
+                .unwrap_err(),
+            EquityError::Deck(DeckError::InvalidCard(hand("2h", "8d")[0]))
+        );
+
+        let report = RangeEquityCalculator::new(vec![
+            Range::from_str("22+").unwrap(),
+            Range::from_str("AKs").unwrap(),
+        ])
+        .variant(Variant::ShortDeck)
+        .monte_carlo(200, StdRng::seed_from_u64(13))
+        .unwrap();
+        // 22-55 are not in a short deck
+        assert_eq!(report.ranges[0].combos.len(), 6 * 9);
+    }
+}
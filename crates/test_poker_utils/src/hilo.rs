@@ -0,0 +1,162 @@
+use crate::{
+    coreutils::{
+        best_ace_to_five, evaluate_best, evaluate_omaha, evaluate_omaha_low, Card, HandValue,
+        LowValue,
+    },
+    showdown::split_chips,
+};
+
+/// Showdown hand of an eight-or-better split pot game
+///
+/// low is None when the player has no qualifying low
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct HiLoHand {
+    pub high: HandValue,
+    pub low: Option<LowValue>,
+}
+
+impl HiLoHand {
+    /// Omaha Hi/Lo: the high and the low each use exactly two hole cards and three board cards,
+    /// possibly different ones
+    pub fn omaha(hole: &[Card], board: &[Card]) -> Option<HiLoHand> {
+        let high = evaluate_omaha(hole, board)?.value;
+        Some(HiLoHand {
+            high,
+            low: evaluate_omaha_low(hole, board),
+        })
+    }
+
+    /// Stud Hi/Lo: the best high and the best low out of 5-7 cards
+    pub fn stud(cards: &[Card]) -> Option<HiLoHand> {
+        let high = evaluate_best(cards)?.value;
+        Some(HiLoHand {
+            high,
+            low: best_ace_to_five(cards).filter(LowValue::is_eight_or_better),
+        })
+    }
+}
+
+/// Split a pot between the hands at showdown, return the amount owed to each hand
+///
+/// the best high takes the whole pot when no low qualifies, otherwise high and low
+/// share it in halves and tied hands share their half. The odd chip of the pot goes
+/// to the high half and odd chips inside a half go to the tied hands in order,
+/// so pass the hands starting from the seat left of the button.
+pub fn split_pot(pot: u64, hands: &[HiLoHand]) -> Vec<u64> {
+    let mut owed = vec![0; hands.len()];
+    let Some(best_high) = hands.iter().map(|hand| hand.high).max() else {
+        return owed;
+    };
+    let high_winners = winners(hands, |hand| hand.high == best_high);
+
+    match hands.iter().filter_map(|hand| hand.low).max() {
+        Some(best_low) => {
+            let low_winners = winners(hands, |hand| hand.low == Some(best_low));
+            split_chips(pot - pot / 2, &high_winners, &mut owed);
+            split_chips(pot / 2, &low_winners, &mut owed);
+        }
+        None => split_chips(pot, &high_winners, &mut owed),
+    }
+
+    owed
+}
+
+fn winners(hands: &[HiLoHand], is_winner: impl Fn(&HiLoHand) -> bool) -> Vec<usize> {
+    hands
+        .iter()
+        .enumerate()
+        .filter(|(_, hand)| is_winner(hand))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[cfg(test)]
+mod hilo_tests {
+    use super::{split_pot, HiLoHand};
+    use crate::{coreutils::HandRank, test_utils::cards};
+
+    fn omaha(hole: &str, board: &str) -> HiLoHand {
+        HiLoHand::omaha(&cards(hole), &cards(board)).unwrap()
+    }
+
+    #[test]
+    fn test_omaha_hands() {
+        let board = "2s5d8cKhKs";
+        let hand = omaha("As3dKdQc", board);
+        // one king in hand only makes trips
+        assert_eq!(hand.high.rank, HandRank::ThreeOfAKind);
+        assert_eq!(hand.low.unwrap().kickers, [8, 5, 3, 2, 1]);
+        assert!(omaha("AsKdQcJh", board).low.is_none());
+        assert!(HiLoHand::omaha(&cards("AsKd"), &cards(board)).is_none());
+    }
+
+    #[test]
+    fn test_stud_hands() {
+        let hand = HiLoHand::stud(&cards("As2s3s4s8sKdKc")).unwrap();
+        assert_eq!(hand.high.rank, HandRank::Flush);
+        assert_eq!(hand.low.unwrap().kickers, [8, 4, 3, 2, 1]);
+        // a nine low does not qualify
+        assert!(HiLoHand::stud(&cards("As2s3s4s9sKdKc"))
+            .unwrap()
+            .low
+            .is_none());
+    }
+
+    #[test]
+    fn test_split_pot() {
+        let board = "2s5d8cKhQs";
+        [
+            // the nut low and a set of kings: halves
+            (
+                vec![omaha("As3dJcTh", board), omaha("KsKdJhTc", board)],
+                100,
+                vec![50, 50],
+            ),
+            // kings with the nut low scoop
+            (
+                vec![omaha("AsKd3c4h", board), omaha("QdJhTc9c", board)],
+                100,
+                vec![100, 0],
+            ),
+            // no qualifying low: the high takes all
+            (
+                vec![omaha("AsAdJcTh", board), omaha("KsKdJhTc", board)],
+                100,
+                vec![0, 100],
+            ),
+            // the same nut low twice gets quartered
+            (
+                vec![
+                    omaha("As3dJcTh", board),
+                    omaha("Ah3hJdTd", board),
+                    omaha("KsKdJhTc", board),
+                ],
+                100,
+                vec![25, 25, 50],
+            ),
+            // odd chip of the pot to the high half
+            (
+                vec![omaha("As3dJcTh", board), omaha("KsKdJhTc", board)],
+                101,
+                vec![50, 51],
+            ),
+            // odd chips inside a half to the first tied hand
+            (
+                vec![
+                    omaha("As3dJcTh", board),
+                    omaha("Ah3hJdTd", board),
+                    omaha("KsKdJhTc", board),
+                ],
+                7,
+                vec![2, 1, 4],
+            ),
+        ]
+        .into_iter()
+        .for_each(|(hands, pot, expect)| {
+            let owed = split_pot(pot, &hands);
+            assert_eq!(owed, expect, "pot {}", pot);
+            assert_eq!(owed.iter().sum::<u64>(), pot);
+        });
+        assert!(split_pot(100, &[]).is_empty());
+    }
+}
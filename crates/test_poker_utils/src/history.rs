@@ -0,0 +1,1505 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    betting::BettingStructure,
+    coreutils::{
+        evaluate_best,
+        evaluator::{rank_name, rank_plural},
+        Card, HandRank, HandValue,
+    },
+    table::{Action, Blind, Deal, Event, Street, Table, TableConfig, TableError, MAX_PLAYERS},
+};
+
+/// Lines about the table rather than the hand, skipped even for players not seated
+const NOTICES: [&str; 10] = [
+    " joins the table",
+    " leaves the table",
+    " has returned",
+    " is disconnected",
+    " is connected",
+    " has timed out",
+    " is sitting out",
+    " will be allowed to play after the button",
+    " was removed from the table",
+    " said, \"",
+];
+
+/// Error for a hand that can't be parsed, line counts from 1 in the parsed text
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseHistoryError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseHistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for ParseHistoryError {}
+
+/// Errors when replaying a hand history through the table
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ReplayError {
+    /// The table can't start the hand: seats, button or cards don't fit it
+    Setup(TableError),
+    /// The table posts other blinds or antes than the history, e.g. a dead blind
+    Posts,
+    /// A post or an action names a player not seated
+    UnknownPlayer(String),
+    /// The table refused the action at this index of the actions
+    Action(usize, TableError),
+    /// The table still waits for an action after the last one
+    Unfinished,
+    /// A player reaches the showdown without known hole cards
+    MissingCards(String),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Setup(err) => write!(f, "can't start the hand: {}", err),
+            ReplayError::Posts => write!(f, "the table posts other blinds or antes"),
+            ReplayError::UnknownPlayer(name) => write!(f, "player `{}` is not seated", name),
+            ReplayError::Action(index, err) => write!(f, "action {} refused: {}", index, err),
+            ReplayError::Unfinished => write!(f, "the hand is not over after the last action"),
+            ReplayError::MissingCards(name) => write!(f, "`{}` reaches the showdown unseen", name),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Player listed in the hand header
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SeatRecord {
+    pub seat: usize,
+    pub name: String,
+    pub stack: u64,
+}
+
+/// Forced bets posted before the hole cards
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum PostKind {
+    Ante,
+    SmallBlind,
+    BigBlind,
+    /// Both blinds at once, posted to come in: the small blind part is dead money
+    SmallAndBigBlinds,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Post {
+    pub player: String,
+    pub kind: PostKind,
+    pub amount: u64,
+    pub all_in: bool,
+}
+
+/// One betting action of a street
+///
+/// action amounts are street totals like the table's, amount is the chips the action put in
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ActionRecord {
+    pub street: Street,
+    pub player: String,
+    pub action: Action,
+    pub amount: u64,
+    pub all_in: bool,
+}
+
+/// A hand as written in a PokerStars hand history
+///
+/// amounts are in cents when the stakes have a currency, in chips otherwise
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct HandHistory {
+    pub id: u64,
+    /// Game and tournament description before the stakes, like "Hold'em No Limit"
+    pub game: String,
+    pub currency: Option<char>,
+    /// Stakes of the header: the blinds, or the small and big bets of limit games
+    pub small_blind: u64,
+    pub big_blind: u64,
+    pub table: String,
+    pub max_seats: Option<usize>,
+    pub button: usize,
+    pub seats: Vec<SeatRecord>,
+    pub posts: Vec<Post>,
+    /// Hole cards known before the showdown, usually only the hero's
+    pub dealt: Vec<(String, Vec<Card>)>,
+    pub actions: Vec<ActionRecord>,
+    pub uncalled: Vec<(String, u64)>,
+    pub board: Vec<Card>,
+    pub shown: Vec<(String, Vec<Card>)>,
+    /// Every pot collected, a player winning a main and a side pot shows up twice
+    pub collected: Vec<(String, u64)>,
+    pub rake: u64,
+}
+
+impl HandHistory {
+    pub fn seat_of(&self, player: &str) -> Option<usize> {
+        self.seats
+            .iter()
+            .find(|seat| seat.name == player)
+            .map(|seat| seat.seat)
+    }
+
+    /// Seats dealt into the hand: players sitting out neither post nor act
+    pub fn players(&self) -> Vec<&SeatRecord> {
+        self.seats
+            .iter()
+            .filter(|seat| {
+                self.posts.iter().any(|post| post.player == seat.name)
+                    || self.actions.iter().any(|action| action.player == seat.name)
+                    || self.cards_of(&seat.name).is_some()
+            })
+            .collect()
+    }
+
+    /// Hole cards of the player, dealt or shown
+    pub fn cards_of(&self, player: &str) -> Option<&[Card]> {
+        self.dealt
+            .iter()
+            .chain(&self.shown)
+            .find(|(name, _)| name == player)
+            .map(|(_, cards)| cards.as_slice())
+    }
+
+    /// Chips the player put in the pot, less any uncalled bet
+    pub fn invested(&self, player: &str) -> u64 {
+        let posted = self
+            .posts
+            .iter()
+            .filter(|post| post.player == player)
+            .map(|post| post.amount);
+        let bet = self
+            .actions
+            .iter()
+            .filter(|action| action.player == player)
+            .map(|action| action.amount);
+        let returned = self
+            .uncalled
+            .iter()
+            .filter(|(name, _)| name == player)
+            .map(|(_, amount)| amount)
+            .sum::<u64>();
+        posted.chain(bet).sum::<u64>().saturating_sub(returned)
+    }
+
+    pub fn collected_by(&self, player: &str) -> u64 {
+        self.collected
+            .iter()
+            .filter(|(name, _)| name == player)
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+
+    /// Chips won or lost in the hand
+    pub fn net(&self, player: &str) -> i64 {
+        self.collected_by(player) as i64 - self.invested(player) as i64
+    }
+}
+
+impl HandHistory {
+    /// Record a hand played on the table, once it is over
+    ///
+    /// the table gives names, stakes and stacks, every player's hole cards become dealt lines
+    pub fn from_events<R: Rng>(
+        id: u64,
+        table_name: &str,
+        table: &Table<R>,
+        events: &[Event],
+    ) -> HandHistory {
+        let config = table.config();
+        let name = |seat: usize| {
+            table
+                .seat(seat)
+                .map_or(String::new(), |seat| seat.name.clone())
+        };
+        let (game, small_blind, big_blind) = match config.betting {
+            BettingStructure::NoLimit => ("Hold'em No Limit", config.small_blind, config.big_blind),
+            BettingStructure::PotLimit => {
+                ("Hold'em Pot Limit", config.small_blind, config.big_blind)
+            }
+            BettingStructure::FixedLimit {
+                small_bet, big_bet, ..
+            } => ("Hold'em Limit", small_bet, big_bet),
+        };
+        let mut hand = HandHistory {
+            id,
+            game: game.to_string(),
+            small_blind,
+            big_blind,
+            table: table_name.to_string(),
+            max_seats: Some(config.seats),
+            ..HandHistory::default()
+        };
+
+        // walk the chips back to the stacks before the hand
+        let mut stacks = (0..config.seats)
+            .map(|seat| table.seat(seat).map_or(0, |seat| seat.stack))
+            .collect::<Vec<_>>();
+        for event in events {
+            match *event {
+                Event::AntePosted { seat, amount }
+                | Event::BlindPosted { seat, amount, .. }
+                | Event::Acted { seat, amount, .. } => stacks[seat] += amount,
+                Event::UncalledBet { seat, amount } | Event::PotAwarded { seat, amount, .. } => {
+                    stacks[seat] -= amount
+                }
+                _ => {}
+            }
+        }
+        let mut dealt_in = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::HoleCards { seat, .. } => Some(*seat),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        dealt_in.sort_unstable();
+        hand.seats = dealt_in
+            .iter()
+            .map(|&seat| SeatRecord {
+                seat: seat + 1,
+                name: name(seat),
+                stack: stacks[seat],
+            })
+            .collect();
+
+        let mut street = Street::Preflop;
+        for event in events {
+            match event {
+                Event::HandStarted { button } => hand.button = button + 1,
+                Event::AntePosted { seat, amount } | Event::BlindPosted { seat, amount, .. } => {
+                    let kind = match event {
+                        Event::BlindPosted {
+                            blind: Blind::Small,
+                            ..
+                        } => PostKind::SmallBlind,
+                        Event::BlindPosted { .. } => PostKind::BigBlind,
+                        _ => PostKind::Ante,
+                    };
+                    stacks[*seat] -= amount;
+                    hand.posts.push(Post {
+                        player: name(*seat),
+                        kind,
+                        amount: *amount,
+                        all_in: stacks[*seat] == 0,
+                    });
+                }
+                Event::HoleCards { seat, cards } => hand.dealt.push((name(*seat), cards.to_vec())),
+                Event::Acted {
+                    seat,
+                    action,
+                    amount,
+                    all_in,
+                } => hand.actions.push(ActionRecord {
+                    street,
+                    player: name(*seat),
+                    action: *action,
+                    amount: *amount,
+                    all_in: *all_in,
+                }),
+                Event::StreetDealt {
+                    street: dealt,
+                    cards,
+                } => {
+                    street = *dealt;
+                    hand.board.extend_from_slice(cards);
+                }
+                Event::UncalledBet { seat, amount } => hand.uncalled.push((name(*seat), *amount)),
+                Event::Shown { seat, cards, .. } => hand.shown.push((name(*seat), cards.to_vec())),
+                Event::PotAwarded { seat, amount, .. } => {
+                    hand.collected.push((name(*seat), *amount))
+                }
+                Event::HandEnded => {}
+            }
+        }
+
+        hand
+    }
+
+    /// Stacks after the hand, in seat order
+    pub fn final_stacks(&self) -> Vec<(String, u64)> {
+        self.seats
+            .iter()
+            .map(|seat| {
+                let stack = seat.stack.saturating_add_signed(self.net(&seat.name));
+                (seat.name.clone(), stack)
+            })
+            .collect()
+    }
+
+    /// Betting structure named in the game, no limit when it names none
+    pub fn betting(&self) -> BettingStructure {
+        if self.game.contains("Pot Limit") {
+            BettingStructure::PotLimit
+        } else if self.game.contains("Limit") && !self.game.contains("No Limit") {
+            BettingStructure::FixedLimit {
+                small_bet: self.small_blind,
+                big_bet: self.big_blind,
+                cap: 4,
+            }
+        } else {
+            BettingStructure::NoLimit
+        }
+    }
+
+    /// Play the hand again through the table and return the stacks after it, in seat order
+    ///
+    /// the table posts the blinds and antes itself and takes no rake, so hands with
+    /// dead blinds or rake don't replay to their final_stacks. Unknown hole cards and
+    /// board cards are dealt by a deck seeded with the hand id.
+    pub fn replay(&self) -> Result<Vec<(String, u64)>, ReplayError> {
+        let betting = self.betting();
+        let posted = |kind| {
+            self.posts
+                .iter()
+                .find(|post| post.kind == kind && !post.all_in)
+                .map(|post| post.amount)
+        };
+        let (small_blind, big_blind) = match betting {
+            // limit stakes are the bets, the blinds are what gets posted
+            BettingStructure::FixedLimit { small_bet, .. } => (
+                posted(PostKind::SmallBlind).unwrap_or(small_bet / 2),
+                posted(PostKind::BigBlind).unwrap_or(small_bet),
+            ),
+            _ => (self.small_blind, self.big_blind),
+        };
+        let ante = self
+            .posts
+            .iter()
+            .filter(|post| post.kind == PostKind::Ante)
+            .map(|post| post.amount)
+            .max()
+            .unwrap_or(0);
+        let size = self
+            .seats
+            .iter()
+            .map(|seat| seat.seat)
+            .chain(self.max_seats)
+            .chain([self.button])
+            .max()
+            .unwrap_or(0);
+        let config = TableConfig::new(small_blind, big_blind)
+            .seats(size)
+            .ante(ante)
+            .betting(betting);
+        let mut table = Table::with_rng(config, StdRng::seed_from_u64(self.id));
+
+        let dealt_in = self.players();
+        let mut hole = vec![];
+        for seat in &dealt_in {
+            let index = seat
+                .seat
+                .checked_sub(1)
+                .ok_or(ReplayError::Setup(TableError::InvalidSeat(0)))?;
+            table
+                .sit(index, &seat.name, seat.stack)
+                .map_err(ReplayError::Setup)?;
+            if let Some(&[a, b]) = self.cards_of(&seat.name) {
+                hole.push((index, [a, b]));
+            }
+        }
+        let deal = Deal {
+            // seats count from 1, a button on seat 0 is out of the table
+            button: Some(self.button.checked_sub(1).unwrap_or(size)),
+            hole,
+            board: self.board.clone(),
+        };
+        let mut events = table.start_hand_with(&deal).map_err(ReplayError::Setup)?;
+
+        let seat_of = |player: &str| {
+            self.seat_of(player)
+                .ok_or(ReplayError::UnknownPlayer(player.to_string()))
+        };
+        let mut expected = self
+            .posts
+            .iter()
+            .map(|post| {
+                Ok((
+                    seat_of(&post.player)?,
+                    post.kind == PostKind::Ante,
+                    post.amount,
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut posts = events
+            .iter()
+            .filter_map(|event| match *event {
+                Event::AntePosted { seat, amount } => Some((seat + 1, true, amount)),
+                Event::BlindPosted { seat, amount, .. } => Some((seat + 1, false, amount)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        expected.sort_unstable();
+        posts.sort_unstable();
+        if posts != expected {
+            return Err(ReplayError::Posts);
+        }
+
+        for (index, action) in self.actions.iter().enumerate() {
+            let seat = seat_of(&action.player)? - 1;
+            let acted = table
+                .act(seat, action.action)
+                .map_err(|err| ReplayError::Action(index, err))?;
+            events.extend(acted);
+        }
+        if !table.is_hand_over() {
+            return Err(ReplayError::Unfinished);
+        }
+        for event in &events {
+            if let Event::Shown { seat, .. } = event {
+                let name = &table.seat(*seat).unwrap().name;
+                if self.cards_of(name).is_none() {
+                    return Err(ReplayError::MissingCards(name.clone()));
+                }
+            }
+        }
+
+        Ok(self
+            .seats
+            .iter()
+            .map(|seat| {
+                let stack = seat
+                    .seat
+                    .checked_sub(1)
+                    .and_then(|index| table.seat(index))
+                    .filter(|_| dealt_in.contains(&seat))
+                    .map_or(seat.stack, |seated| seated.stack);
+                (seat.name.clone(), stack)
+            })
+            .collect())
+    }
+
+    /// "$1.05" with a currency, "105" in chips
+    fn money(&self, amount: u64) -> String {
+        match self.currency {
+            Some(currency) => format!("{}{}.{:02}", currency, amount / 100, amount % 100),
+            None => amount.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for HandHistory {
+    /// PokerStars text, parsed back to the same hand
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "PokerStars Hand #{}: {} ({}/{})",
+            self.id,
+            self.game,
+            self.money(self.small_blind),
+            self.money(self.big_blind)
+        )?;
+        let max_seats = self
+            .max_seats
+            .map_or(String::new(), |max| format!(" {}-max", max));
+        writeln!(
+            f,
+            "Table '{}'{} Seat #{} is the button",
+            self.table, max_seats, self.button
+        )?;
+        for seat in &self.seats {
+            let stack = self.money(seat.stack);
+            writeln!(f, "Seat {}: {} ({} in chips)", seat.seat, seat.name, stack)?;
+        }
+
+        // street totals, to write raises as "raises 20 to 30"
+        let mut bets: HashMap<&str, u64> = HashMap::new();
+        for post in &self.posts {
+            let (kind, live) = match post.kind {
+                PostKind::Ante => ("the ante", 0),
+                PostKind::SmallBlind => ("small blind", post.amount),
+                PostKind::BigBlind => ("big blind", post.amount),
+                PostKind::SmallAndBigBlinds => {
+                    ("small & big blinds", post.amount.min(self.big_blind))
+                }
+            };
+            *bets.entry(&post.player).or_default() += live;
+            let amount = self.money(post.amount);
+            writeln!(
+                f,
+                "{}: posts {} {}{}",
+                post.player,
+                kind,
+                amount,
+                all_in(post.all_in)
+            )?;
+        }
+        writeln!(f, "*** HOLE CARDS ***")?;
+        for (player, cards) in &self.dealt {
+            writeln!(f, "Dealt to {} {}", player, brackets(cards))?;
+        }
+
+        let last_street = self
+            .actions
+            .last()
+            .map_or(Street::Preflop, |action| action.street);
+        let mut current = bets.values().copied().max().unwrap_or(0);
+        for street in [Street::Preflop, Street::Flop, Street::Turn, Street::River] {
+            let (marker, size) = match street {
+                Street::Preflop => ("", 0),
+                Street::Flop => ("FLOP", 3),
+                Street::Turn => ("TURN", 4),
+                Street::River => ("RIVER", 5),
+            };
+            if self.board.len() < size {
+                break;
+            }
+            match street {
+                Street::Preflop => {}
+                Street::Flop => writeln!(f, "*** FLOP *** {}", brackets(&self.board[..3]))?,
+                _ => writeln!(
+                    f,
+                    "*** {} *** {} {}",
+                    marker,
+                    brackets(&self.board[..size - 1]),
+                    brackets(&self.board[size - 1..size])
+                )?,
+            }
+            if street != Street::Preflop {
+                bets.clear();
+                current = 0;
+            }
+
+            for action in self.actions.iter().filter(|action| action.street == street) {
+                let bet = bets.entry(&action.player).or_default();
+                *bet += action.amount;
+                let amount = self.money(action.amount);
+                let text = match action.action {
+                    Action::Fold => "folds".to_string(),
+                    Action::Check => "checks".to_string(),
+                    Action::Call => format!("calls {}", amount),
+                    Action::AllIn if *bet <= current => format!("calls {}", amount),
+                    Action::Bet(_) => format!("bets {}", amount),
+                    Action::AllIn if current == 0 => format!("bets {}", amount),
+                    Action::Raise(_) | Action::AllIn => format!(
+                        "raises {} to {}",
+                        self.money(bet.saturating_sub(current)),
+                        self.money(*bet)
+                    ),
+                };
+                current = current.max(*bet);
+                writeln!(f, "{}: {}{}", action.player, text, all_in(action.all_in))?;
+            }
+            if street == last_street {
+                for (player, amount) in &self.uncalled {
+                    let amount = self.money(*amount);
+                    writeln!(f, "Uncalled bet ({}) returned to {}", amount, player)?;
+                }
+            }
+        }
+
+        if !self.shown.is_empty() {
+            writeln!(f, "*** SHOW DOWN ***")?;
+        }
+        for (player, cards) in &self.shown {
+            let mut hand = self.board.clone();
+            hand.extend_from_slice(cards);
+            match evaluate_best(&hand) {
+                Some(best) => {
+                    let value = describe(&best.value);
+                    writeln!(f, "{}: shows {} ({})", player, brackets(cards), value)?
+                }
+                None => writeln!(f, "{}: shows {}", player, brackets(cards))?,
+            }
+        }
+        for (player, amount) in &self.collected {
+            writeln!(f, "{} collected {} from pot", player, self.money(*amount))?;
+        }
+
+        writeln!(f, "*** SUMMARY ***")?;
+        let total = self.collected.iter().map(|(_, amount)| amount).sum::<u64>() + self.rake;
+        writeln!(
+            f,
+            "Total pot {} | Rake {}",
+            self.money(total),
+            self.money(self.rake)
+        )?;
+        if !self.board.is_empty() {
+            writeln!(f, "Board {}", brackets(&self.board))?;
+        }
+        Ok(())
+    }
+}
+
+fn all_in(all_in: bool) -> &'static str {
+    if all_in {
+        " and is all-in"
+    } else {
+        ""
+    }
+}
+
+/// Shown hand in the history's wording, e.g. "a full house, Kings full of Sevens"
+fn describe(value: &HandValue) -> String {
+    let [first, second, ..] = value.kickers;
+    let straight = |high: u8| {
+        // the wheel runs from the Ace
+        let low = if high == 5 { 14 } else { high - 4 };
+        format!("{} to {}", rank_name(low), rank_name(high))
+    };
+    match value.rank {
+        HandRank::HighCard => format!("high card {}", rank_name(first)),
+        HandRank::OnePair => format!("a pair of {}", rank_plural(first)),
+        HandRank::TwoPair => format!(
+            "two pair, {} and {}",
+            rank_plural(first),
+            rank_plural(second)
+        ),
+        HandRank::ThreeOfAKind => format!("three of a kind, {}", rank_plural(first)),
+        HandRank::Straight => format!("a straight, {}", straight(first)),
+        HandRank::Flush => format!("a flush, {} high", rank_name(first)),
+        HandRank::FullHouse => format!(
+            "a full house, {} full of {}",
+            rank_plural(first),
+            rank_plural(second)
+        ),
+        HandRank::FourOfAKind => format!("four of a kind, {}", rank_plural(first)),
+        HandRank::StraightFlush => format!("a straight flush, {}", straight(first)),
+        HandRank::RoyalFlush => "a Royal Flush".to_string(),
+    }
+}
+
+fn brackets(cards: &[Card]) -> String {
+    let cards = cards.iter().map(Card::to_string).collect::<Vec<_>>();
+    format!("[{}]", cards.join(" "))
+}
+
+impl FromStr for HandHistory {
+    type Err = ParseHistoryError;
+
+    /// Parse a single hand
+    fn from_str(s: &str) -> Result<HandHistory, ParseHistoryError> {
+        let lines = s
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .collect::<Vec<_>>();
+        parse_hand(&lines)
+    }
+}
+
+/// Parse every hand of a hand history file
+///
+/// a hand starts at its "PokerStars" header line, a malformed hand gives an error
+/// with its line number in the file and the following hands are still parsed
+pub fn parse_histories(text: &str) -> Vec<Result<HandHistory, ParseHistoryError>> {
+    let mut hands: Vec<Vec<(usize, &str)>> = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_start_matches('\u{feff}').trim();
+        if line.starts_with("PokerStars ") || (hands.is_empty() && !line.is_empty()) {
+            hands.push(vec![]);
+        }
+        if let Some(hand) = hands.last_mut() {
+            hand.push((index + 1, line));
+        }
+    }
+
+    hands.iter().map(|lines| parse_hand(lines)).collect()
+}
+
+fn parse_hand(lines: &[(usize, &str)]) -> Result<HandHistory, ParseHistoryError> {
+    let mut parser = HandParser::default();
+    let mut last = lines.first().map_or(1, |&(number, _)| number);
+    for &(number, line) in lines.iter().filter(|(_, line)| !line.is_empty()) {
+        parser.line(line).map_err(|reason| ParseHistoryError {
+            line: number,
+            reason,
+        })?;
+        last = number;
+    }
+    parser
+        .finish()
+        .map_err(|reason| ParseHistoryError { line: last, reason })
+}
+
+/// Part of the hand the next line belongs to
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+enum Section {
+    #[default]
+    Header,
+    Table,
+    Seats,
+    Betting(Street),
+    Showdown,
+    Summary,
+}
+
+#[derive(Debug, Default)]
+struct HandParser {
+    hand: HandHistory,
+    section: Section,
+    // chips put in on the current street, by index in hand.seats
+    street_bets: Vec<u64>,
+}
+
+impl HandParser {
+    fn line(&mut self, line: &str) -> Result<(), String> {
+        match self.section {
+            Section::Header => {
+                self.section = Section::Table;
+                return self.header(line);
+            }
+            Section::Table => {
+                self.section = Section::Seats;
+                return self.table(line);
+            }
+            _ => {}
+        }
+
+        if let Some(marker) = line.strip_prefix("*** ") {
+            return self.marker(marker);
+        }
+        if self.section == Section::Summary {
+            return self.summary(line);
+        }
+        if let Some(seat) = line.strip_prefix("Seat ") {
+            if self.section == Section::Seats {
+                return self.seat(seat);
+            }
+        }
+        if let Some(dealt) = line.strip_prefix("Dealt to ") {
+            let split = dealt.find(" [").ok_or("missing hole cards")?;
+            let index = self.player(&dealt[..split])?;
+            let cards = parse_cards(&dealt[split + 1..])?;
+            self.hand.dealt.push((self.name(index), cards));
+            return Ok(());
+        }
+        if let Some(uncalled) = line.strip_prefix("Uncalled bet (") {
+            let (amount, name) = uncalled
+                .split_once(") returned to ")
+                .ok_or("missing player of the uncalled bet")?;
+            let amount = self.amount(amount)?;
+            let index = self.player(name)?;
+            let bet = self.street_bets[index];
+            if amount > bet {
+                return Err(format!(
+                    "uncalled bet {} is more than the {} {} put in",
+                    amount, bet, name
+                ));
+            }
+            self.street_bets[index] -= amount;
+            self.hand.uncalled.push((self.name(index), amount));
+            return Ok(());
+        }
+        if let Some((index, rest)) = self.player_prefix(line) {
+            if let Some(action) = rest.strip_prefix(": ") {
+                return self.action(index, action);
+            }
+            if let Some(collected) = rest.strip_prefix(" collected ") {
+                let amount = collected.split_whitespace().next().unwrap_or_default();
+                let amount = self.amount(amount)?;
+                self.hand.collected.push((self.name(index), amount));
+                return Ok(());
+            }
+            if NOTICES.iter().any(|notice| rest.starts_with(notice)) {
+                return Ok(());
+            }
+        }
+        if NOTICES.iter().any(|notice| line.contains(notice)) {
+            return Ok(());
+        }
+
+        Err(format!("unrecognized line `{}`", line))
+    }
+
+    /// PokerStars Hand #1: Hold'em No Limit ($0.01/$0.02 USD) - 2021/06/01 20:15:00 ET
+    fn header(&mut self, line: &str) -> Result<(), String> {
+        let rest = line
+            .strip_prefix("PokerStars ")
+            .ok_or("expected a PokerStars hand header")?;
+        let (id, rest) = rest
+            .split_once('#')
+            .and_then(|(_, rest)| rest.split_once(": "))
+            .ok_or("missing hand number")?;
+        self.hand.id = digits(id).ok_or(format!("invalid hand number `{}`", id))?;
+
+        let (start, small_blind, big_blind) = rest
+            .match_indices('(')
+            .find_map(|(start, _)| {
+                let stakes = rest[start + 1..].split(')').next()?;
+                let (small_blind, big_blind) = stakes.split_whitespace().next()?.split_once('/')?;
+                Some((start, small_blind, big_blind))
+            })
+            .ok_or("missing stakes")?;
+        self.hand.game = rest[..start].trim().to_string();
+        self.hand.currency = small_blind.chars().next().filter(|c| !c.is_ascii_digit());
+        self.hand.small_blind = self.amount(small_blind)?;
+        self.hand.big_blind = self.amount(big_blind)?;
+        Ok(())
+    }
+
+    /// Table 'Alcor II' 6-max Seat #4 is the button
+    fn table(&mut self, line: &str) -> Result<(), String> {
+        let (table, rest) = line
+            .strip_prefix("Table '")
+            .and_then(|rest| rest.rsplit_once('\''))
+            .ok_or("expected the table line")?;
+        self.hand.table = table.to_string();
+        self.hand.max_seats = rest
+            .split_whitespace()
+            .find_map(|token| digits(token.strip_suffix("-max")?).map(|max| max as usize));
+        if let Some(max) = self.hand.max_seats.filter(|&max| max > MAX_PLAYERS) {
+            return Err(format!("table of {} seats is too large", max));
+        }
+        let button = rest
+            .split_once("Seat #")
+            .and_then(|(_, button)| button.split_whitespace().next())
+            .ok_or("missing button seat")?;
+        self.hand.button = digits(button)
+            .map(|button| button as usize)
+            .filter(|&button| button <= self.max_seat())
+            .ok_or(format!("invalid seat `{}`", button))?;
+        Ok(())
+    }
+
+    /// Seat 1: Alice ($10.00 in chips) is sitting out
+    fn seat(&mut self, line: &str) -> Result<(), String> {
+        let (seat, rest) = line.split_once(": ").ok_or("missing seat number")?;
+        let seat = digits(seat)
+            .map(|seat| seat as usize)
+            .filter(|&seat| (1..=self.max_seat()).contains(&seat))
+            .ok_or(format!("invalid seat `{}`", seat))?;
+        let (name, stack) = rest
+            .split_once(" in chips")
+            .and_then(|(rest, _)| rest.rsplit_once(" ("))
+            .ok_or("missing stack")?;
+        if self.hand.seat_of(name).is_some() || self.hand.seats.iter().any(|s| s.seat == seat) {
+            return Err(format!("seat {} or player `{}` listed twice", seat, name));
+        }
+        let stack = self.amount(stack)?;
+        self.hand.seats.push(SeatRecord {
+            seat,
+            name: name.to_string(),
+            stack,
+        });
+        self.street_bets.push(0);
+        Ok(())
+    }
+
+    /// Highest seat number, the table size or MAX_PLAYERS when the header doesn't say
+    fn max_seat(&self) -> usize {
+        self.hand.max_seats.unwrap_or(MAX_PLAYERS)
+    }
+
+    /// *** FLOP *** [Ks 7d 2c]
+    fn marker(&mut self, marker: &str) -> Result<(), String> {
+        let (name, rest) = marker
+            .split_once(" ***")
+            .ok_or("unterminated section marker")?;
+        let (street, size) = match name {
+            "HOLE CARDS" => {
+                self.section = Section::Betting(Street::Preflop);
+                return Ok(());
+            }
+            "SHOW DOWN" => {
+                self.section = Section::Showdown;
+                return Ok(());
+            }
+            "SUMMARY" => {
+                self.section = Section::Summary;
+                return Ok(());
+            }
+            "FLOP" => (Street::Flop, 3),
+            "TURN" => (Street::Turn, 4),
+            "RIVER" => (Street::River, 5),
+            _ => return Err(format!("unsupported section `{}`", name)),
+        };
+
+        if !matches!(self.section, Section::Betting(_)) {
+            return Err(format!("{:?} outside the betting rounds", street));
+        }
+        // the new cards are in the last brackets
+        let cards = parse_cards(&rest[rest.rfind('[').unwrap_or(rest.len())..])?;
+        if self.hand.board.len() + cards.len() != size {
+            return Err(format!("{:?} needs a board of {} cards", street, size));
+        }
+        self.hand.board.extend(cards);
+        self.street_bets.iter_mut().for_each(|bet| *bet = 0);
+        self.section = Section::Betting(street);
+        Ok(())
+    }
+
+    /// Total pot $14.85 | Rake $0.58
+    fn summary(&mut self, line: &str) -> Result<(), String> {
+        if line.starts_with("Total pot ") {
+            if let Some((_, rake)) = line.split_once("| Rake ") {
+                let rake = rake.split_whitespace().next().unwrap_or_default();
+                self.hand.rake = self.amount(rake)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The part of a "player: action" line after the colon
+    fn action(&mut self, index: usize, action: &str) -> Result<(), String> {
+        let (action, all_in) = match action.strip_suffix(" and is all-in") {
+            Some(action) => (action, true),
+            None => (action, false),
+        };
+        if let Some(post) = action.strip_prefix("posts ") {
+            return self.post(index, post, all_in);
+        }
+        if let Some(cards) = action.strip_prefix("shows ") {
+            let cards = parse_cards(cards)?;
+            self.hand.shown.push((self.name(index), cards));
+            return Ok(());
+        }
+        if action.starts_with("mucks")
+            || action.starts_with("doesn't show")
+            || ["is sitting out", "sits out", "is back"].contains(&action)
+        {
+            return Ok(());
+        }
+
+        let Section::Betting(street) = self.section else {
+            return Err(format!("`{}` outside the betting rounds", action));
+        };
+        let (action, amount) = match action.split_whitespace().collect::<Vec<_>>()[..] {
+            // a fold may show the folded cards
+            ["folds", ..] => (Action::Fold, 0),
+            ["checks"] => (Action::Check, 0),
+            ["calls", amount] => (Action::Call, self.amount(amount)?),
+            ["bets", amount] => {
+                let amount = self.amount(amount)?;
+                (Action::Bet(self.street_bets[index] + amount), amount)
+            }
+            ["raises", _, "to", total] => {
+                let total = self.amount(total)?;
+                let amount = total
+                    .checked_sub(self.street_bets[index])
+                    .ok_or(format!("raise to {} is less than the player's bet", total))?;
+                (Action::Raise(total), amount)
+            }
+            _ => return Err(format!("unrecognized action `{}`", action)),
+        };
+        self.street_bets[index] += amount;
+        self.hand.actions.push(ActionRecord {
+            street,
+            player: self.name(index),
+            action,
+            amount,
+            all_in,
+        });
+        Ok(())
+    }
+
+    fn post(&mut self, index: usize, post: &str, all_in: bool) -> Result<(), String> {
+        let (kind, amount) = [
+            ("the ante ", PostKind::Ante),
+            ("small blind ", PostKind::SmallBlind),
+            ("big blind ", PostKind::BigBlind),
+            ("small & big blinds ", PostKind::SmallAndBigBlinds),
+        ]
+        .into_iter()
+        .find_map(|(prefix, kind)| Some((kind, post.strip_prefix(prefix)?)))
+        .ok_or(format!("unrecognized post `{}`", post))?;
+        if self.section != Section::Seats {
+            return Err("post after the hole cards".to_string());
+        }
+
+        let amount = self.amount(amount)?;
+        self.street_bets[index] += match kind {
+            PostKind::Ante => 0,
+            PostKind::SmallBlind | PostKind::BigBlind => amount,
+            PostKind::SmallAndBigBlinds => amount.min(self.hand.big_blind),
+        };
+        self.hand.posts.push(Post {
+            player: self.name(index),
+            kind,
+            amount,
+            all_in,
+        });
+        Ok(())
+    }
+
+    fn finish(self) -> Result<HandHistory, String> {
+        match self.section {
+            Section::Header => Err("missing hand header".to_string()),
+            Section::Table => Err("missing table line".to_string()),
+            Section::Seats => Err("missing hole cards section".to_string()),
+            _ if self.hand.seats.is_empty() => Err("no player seated".to_string()),
+            _ => Ok(self.hand),
+        }
+    }
+
+    /// Index of the seated player with the longest name starting the line, and the rest of the line
+    fn player_prefix<'a>(&self, line: &'a str) -> Option<(usize, &'a str)> {
+        self.hand
+            .seats
+            .iter()
+            .enumerate()
+            .filter(|(_, seat)| {
+                line.strip_prefix(seat.name.as_str())
+                    .is_some_and(|rest| rest.starts_with(':') || rest.starts_with(' '))
+            })
+            .max_by_key(|(_, seat)| seat.name.len())
+            .map(|(index, seat)| (index, &line[seat.name.len()..]))
+    }
+
+    fn player(&self, name: &str) -> Result<usize, String> {
+        self.hand
+            .seats
+            .iter()
+            .position(|seat| seat.name == name)
+            .ok_or(format!("unknown player `{}`", name))
+    }
+
+    fn name(&self, index: usize) -> String {
+        self.hand.seats[index].name.clone()
+    }
+
+    fn amount(&self, amount: &str) -> Result<u64, String> {
+        parse_amount(amount, self.hand.currency).ok_or(format!("invalid amount `{}`", amount))
+    }
+}
+
+/// "$1.05" is 105 cents, "1500" is 1500 chips
+fn parse_amount(amount: &str, currency: Option<char>) -> Option<u64> {
+    let Some(currency) = currency else {
+        return digits(amount);
+    };
+    let amount = amount.strip_prefix(currency)?;
+    match amount.split_once('.') {
+        None => Some(digits(amount)? * 100),
+        Some((whole, cents)) => {
+            let cents = match cents.len() {
+                1 => digits(cents)? * 10,
+                2 => digits(cents)?,
+                _ => return None,
+            };
+            Some(digits(whole)? * 100 + cents)
+        }
+    }
+}
+
+fn digits(s: &str) -> Option<u64> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Cards of the first brackets: "[Ah Kd] (a pair of Kings)"
+fn parse_cards(s: &str) -> Result<Vec<Card>, String> {
+    let cards = s
+        .strip_prefix('[')
+        .and_then(|s| s.split_once(']'))
+        .map(|(cards, _)| cards)
+        .ok_or(format!("expected cards in brackets, found `{}`", s))?;
+    cards
+        .split_whitespace()
+        .map(|card| {
+            card.parse()
+                .map_err(|err| format!("invalid card `{}`: {}", card, err))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod history_tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    use super::{describe, parse_histories, HandHistory, PostKind, ReplayError, SeatRecord};
+    use crate::{
+        betting::BettingStructure,
+        coreutils::evaluate,
+        table::{Action, Street, Table, TableConfig, TableError},
+        test_utils::cards,
+    };
+
+    const CASH: &str = r#"PokerStars Hand #230000000001: Hold'em No Limit ($0.05/$0.10 USD) - 2021/06/01 20:15:00 ET
+Table 'Alcor II' 6-max Seat #4 is the button
+Seat 1: Alice ($10.00 in chips)
+Seat 2: Bob ($7.35 in chips)
+Seat 4: Carol ($12.10 in chips)
+Seat 6: Dave ($10 in chips)
+Dave: posts small blind $0.05
+Alice: posts big blind $0.10
+*** HOLE CARDS ***
+Dealt to Carol [Ah Kd]
+Erin joins the table at seat #3
+Bob: calls $0.10
+Carol: raises $0.30 to $0.40
+Dave: folds
+Alice: folds
+Dave said, "nh"
+Bob: calls $0.30
+*** FLOP *** [Ks 7d 2c]
+Bob: checks
+Carol: bets $0.55
+Bob: raises $1.10 to $1.65
+Carol: calls $1.10
+*** TURN *** [Ks 7d 2c] [9h]
+Bob: bets $5.30 and is all-in
+Carol: calls $5.30
+*** RIVER *** [Ks 7d 2c 9h] [3s]
+*** SHOW DOWN ***
+Bob: shows [7s 7c] (three of a kind, Sevens)
+Carol: shows [Ah Kd] (a pair of Kings)
+Bob collected $14.27 from pot
+*** SUMMARY ***
+Total pot $14.85 | Rake $0.58
+Board [Ks 7d 2c 9h 3s]
+Seat 1: Alice (big blind) folded before Flop
+Seat 2: Bob showed [7s 7c] and won ($14.27) with three of a kind, Sevens
+Seat 4: Carol (button) showed [Ah Kd] and lost with a pair of Kings
+Seat 6: Dave (small blind) folded before Flop"#;
+
+    const TOURNAMENT: &str = r#"PokerStars Hand #230000000002: Tournament #3100000000, $4.40+$0.60 USD Hold'em No Limit - Level IV (50/100) - 2021/06/01 20:20:00 ET
+Table '3100000000 7' 9-max Seat #2 is the button
+Seat 1: Erin (2400 in chips)
+Seat 2: Frank (800 in chips, $2 bounty)
+Seat 3: Gina (5000 in chips)
+Erin: posts the ante 10
+Frank: posts the ante 10
+Gina: posts the ante 10
+Gina: posts small blind 50
+Erin: posts big blind 100
+*** HOLE CARDS ***
+Frank: raises 690 to 790 and is all-in
+Gina: raises 1200 to 1990
+Erin: folds
+Uncalled bet (1200) returned to Gina
+*** FLOP *** [Qs Jd 4c]
+*** TURN *** [Qs Jd 4c] [Tc]
+*** RIVER *** [Qs Jd 4c Tc] [2h]
+*** SHOW DOWN ***
+Frank: shows [Ah Ad] (a pair of Aces)
+Gina: shows [Kc Qh] (a pair of Queens)
+Frank collected 1710 from pot
+*** SUMMARY ***
+Total pot 1710 | Rake 0
+Board [Qs Jd 4c Tc 2h]"#;
+
+    /// Line number of the first line starting with prefix
+    fn line_of(text: &str, prefix: &str) -> usize {
+        text.lines()
+            .position(|line| line.starts_with(prefix))
+            .unwrap()
+            + 1
+    }
+
+    #[test]
+    fn test_cash_hand() {
+        let hand: HandHistory = CASH.parse().unwrap();
+        assert_eq!(hand.id, 230000000001);
+        assert_eq!(hand.game, "Hold'em No Limit");
+        assert_eq!(hand.currency, Some('$'));
+        assert_eq!((hand.small_blind, hand.big_blind), (5, 10));
+        assert_eq!(hand.table, "Alcor II");
+        assert_eq!((hand.max_seats, hand.button), (Some(6), 4));
+        assert_eq!(
+            hand.seats
+                .iter()
+                .map(|seat| (seat.seat, seat.name.as_str(), seat.stack))
+                .collect::<Vec<_>>(),
+            [
+                (1, "Alice", 1000),
+                (2, "Bob", 735),
+                (4, "Carol", 1210),
+                (6, "Dave", 1000)
+            ]
+        );
+        assert_eq!(hand.posts[0].kind, PostKind::SmallBlind);
+        assert_eq!(hand.posts[1].amount, 10);
+        assert_eq!(hand.cards_of("Carol"), Some(&cards("AhKd")[..]));
+        assert_eq!(hand.cards_of("Bob"), Some(&cards("7s7c")[..]));
+        assert_eq!(hand.cards_of("Dave"), None);
+        assert_eq!(hand.board, cards("Ks7d2c9h3s"));
+
+        assert_eq!(hand.actions.len(), 11);
+        let raise = &hand.actions[7];
+        assert_eq!(raise.street, Street::Flop);
+        assert_eq!((raise.action, raise.amount), (Action::Raise(165), 165));
+        let shove = &hand.actions[9];
+        assert_eq!((shove.action, shove.all_in), (Action::Bet(530), true));
+
+        assert_eq!(hand.collected, [("Bob".to_string(), 1427)]);
+        assert_eq!(hand.rake, 58);
+        assert_eq!(hand.invested("Bob"), 735);
+        assert_eq!(hand.net("Bob"), 692);
+        assert_eq!(hand.net("Carol"), -735);
+        assert_eq!(hand.net("Alice"), -10);
+    }
+
+    #[test]
+    fn test_tournament_hand() {
+        let hand: HandHistory = TOURNAMENT.parse().unwrap();
+        assert_eq!(hand.currency, None);
+        assert_eq!((hand.small_blind, hand.big_blind), (50, 100));
+        assert_eq!(hand.seats[1].stack, 800);
+        assert_eq!(
+            hand.posts
+                .iter()
+                .filter(|post| post.kind == PostKind::Ante)
+                .count(),
+            3
+        );
+        // the small blind raises from 50 to 1990
+        assert_eq!(hand.actions[1].amount, 1940);
+        assert_eq!(hand.uncalled, [("Gina".to_string(), 1200)]);
+        assert_eq!(hand.invested("Gina"), 800);
+        assert_eq!(hand.net("Frank"), 910);
+        assert_eq!(hand.net("Erin"), -110);
+        // every chip won was lost by someone
+        let total = hand
+            .seats
+            .iter()
+            .map(|seat| hand.net(&seat.name))
+            .sum::<i64>();
+        assert_eq!(total, 0);
+    }
+
+    #[test]
+    fn test_file_with_malformed_hand() {
+        let bad = CASH.replace("[7s 7c]", "[7s 1c]");
+        let file = format!("\u{feff}{}\n\n\n{}\n\n{}\n", CASH, bad, TOURNAMENT);
+        let hands = parse_histories(&file);
+        assert_eq!(hands.len(), 3);
+        assert_eq!(hands[0].as_ref().unwrap().id, 230000000001);
+        assert_eq!(hands[2].as_ref().unwrap().id, 230000000002);
+
+        let err = hands[1].as_ref().unwrap_err();
+        let offset = CASH.lines().count() + 2;
+        assert_eq!(err.line, offset + line_of(CASH, "Bob: shows"));
+        assert!(err.to_string().contains("invalid card `1c`"), "{}", err);
+    }
+
+    #[test]
+    fn test_errors() {
+        [
+            (
+                "Hand #230000000001:",
+                "Hand #23x:",
+                "PokerStars",
+                "invalid hand number",
+            ),
+            ("($0.05/$0.10 USD)", "", "PokerStars", "missing stakes"),
+            ("Seat #4 is the button", "", "Table", "missing button"),
+            ("Seat #4", "Seat #7", "Table", "invalid seat `7`"),
+            ("6-max", "4000000000-max", "Table", "too large"),
+            (
+                "Seat 1: Alice",
+                "Seat 0: Alice",
+                "Seat 0",
+                "invalid seat `0`",
+            ),
+            (
+                "Seat 1: Alice",
+                "Seat 7: Alice",
+                "Seat 7",
+                "invalid seat `7`",
+            ),
+            (
+                "($7.35 in chips)",
+                "(7.35 in chips)",
+                "Seat 2",
+                "invalid amount",
+            ),
+            ("Carol: bets", "Zed: bets", "Zed", "unrecognized line"),
+            (
+                "Bob collected",
+                "Bob collects",
+                "Bob collects",
+                "unrecognized line",
+            ),
+            (
+                "Bob: checks",
+                "Bob: limps",
+                "Bob: limps",
+                "unrecognized action",
+            ),
+            (
+                "*** HOLE CARDS ***",
+                "",
+                "Bob: calls",
+                "outside the betting rounds",
+            ),
+            (
+                "[Ks 7d 2c] [9h]",
+                "[Ks 7d 2c] [9h 8h]",
+                "*** TURN",
+                "board of 4",
+            ),
+            (
+                "*** SHOW DOWN ***",
+                "*** FIRST SHOW DOWN ***",
+                "*** FIRST",
+                "unsupported",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(from, to, line, reason)| {
+            let text = CASH.replacen(from, to, 1);
+            let err = text.parse::<HandHistory>().unwrap_err();
+            assert_eq!(err.line, line_of(&text, line), "{}", err);
+            assert!(err.reason.contains(reason), "{}", err);
+        });
+
+        // Gina raised to 1990
+        let text = TOURNAMENT.replacen("Uncalled bet (1200)", "Uncalled bet (2000)", 1);
+        let err = text.parse::<HandHistory>().unwrap_err();
+        assert_eq!(err.line, line_of(&text, "Uncalled bet"));
+        assert!(err.reason.contains("more than the 1990"), "{}", err);
+
+        let err = "PokerStars Hand #1: Hold'em No Limit (1/2)"
+            .parse::<HandHistory>()
+            .unwrap_err();
+        assert_eq!((err.line, err.reason.as_str()), (1, "missing table line"));
+    }
+
+    #[test]
+    fn test_write_parses_back() {
+        [CASH, TOURNAMENT].into_iter().for_each(|text| {
+            let hand: HandHistory = text.parse().unwrap();
+            let written = hand.to_string();
+            assert_eq!(written.parse::<HandHistory>().unwrap(), hand, "{}", written);
+        });
+
+        let written = CASH.parse::<HandHistory>().unwrap().to_string();
+        [
+            "PokerStars Hand #230000000001: Hold'em No Limit ($0.05/$0.10)",
+            "Table 'Alcor II' 6-max Seat #4 is the button",
+            "Seat 6: Dave ($10.00 in chips)",
+            "Bob: raises $1.10 to $1.65",
+            "Bob: bets $5.30 and is all-in",
+            "*** RIVER *** [Ks 7d 2c 9h] [3s]",
+            "Bob: shows [7s 7c] (three of a kind, Sevens)",
+            "Carol: shows [Ah Kd] (a pair of Kings)",
+            "Total pot $14.85 | Rake $0.58",
+        ]
+        .into_iter()
+        .for_each(|line| assert!(written.lines().any(|l| l == line), "{}", line));
+        let written = TOURNAMENT.parse::<HandHistory>().unwrap().to_string();
+        assert!(written.contains("Gina: raises 1200 to 1990\nErin: folds\nUncalled bet (1200)"));
+    }
+
+    #[test]
+    fn test_describe() {
+        [
+            ("Ah9d7c4s2h", "high card Ace"),
+            ("KhKd7c4s2h", "a pair of Kings"),
+            ("AhAd6c6s2h", "two pair, Aces and Sixes"),
+            ("As2d3c4s5h", "a straight, Ace to Five"),
+            ("9s8d7cTsJh", "a straight, Seven to Jack"),
+            ("Kh9h7h4h2h", "a flush, King high"),
+            ("KhKdKs7c7h", "a full house, Kings full of Sevens"),
+            ("5h5d5s5c7h", "four of a kind, Fives"),
+            ("9h8h7h6h5h", "a straight flush, Five to Nine"),
+            ("AhKhQhJhTh", "a Royal Flush"),
+        ]
+        .into_iter()
+        .for_each(|(hand, expect)| {
+            assert_eq!(describe(&evaluate(&cards(hand))), expect);
+        });
+    }
+
+    #[test]
+    fn test_replay() {
+        let hand: HandHistory = TOURNAMENT.parse().unwrap();
+        let stacks = hand.replay().unwrap();
+        assert_eq!(stacks, hand.final_stacks());
+        assert_eq!(stacks[1], ("Frank".to_string(), 1710));
+
+        // the table takes no rake
+        let mut hand: HandHistory = CASH.parse().unwrap();
+        assert_eq!(hand.replay().unwrap()[1], ("Bob".to_string(), 1485));
+        assert_eq!(hand.final_stacks()[1], ("Bob".to_string(), 1427));
+        hand.rake = 0;
+        hand.collected[0].1 = 1485;
+        assert_eq!(hand.replay().unwrap(), hand.final_stacks());
+    }
+
+    #[test]
+    fn test_replay_errors() {
+        let hand: HandHistory = TOURNAMENT.parse().unwrap();
+        let mut unseen = hand.clone();
+        unseen.shown.remove(0);
+        assert_eq!(
+            unseen.replay(),
+            Err(ReplayError::MissingCards("Frank".to_string()))
+        );
+        let mut dead_button = hand.clone();
+        dead_button.button = 1;
+        assert_eq!(dead_button.replay(), Err(ReplayError::Posts));
+        let mut swapped = hand.clone();
+        swapped.actions.swap(0, 1);
+        assert_eq!(
+            swapped.replay(),
+            Err(ReplayError::Action(0, TableError::OutOfTurn(2)))
+        );
+        let mut unfinished = hand.clone();
+        unfinished.actions.pop();
+        assert_eq!(unfinished.replay(), Err(ReplayError::Unfinished));
+        // a seat 0 player sitting out keeps the stack
+        let mut seat_zero = hand.clone();
+        seat_zero.seats.push(SeatRecord {
+            seat: 0,
+            name: "Zed".to_string(),
+            stack: 500,
+        });
+        let stacks = seat_zero.replay().unwrap();
+        assert_eq!(stacks.last(), Some(&("Zed".to_string(), 500)));
+    }
+
+    #[test]
+    fn test_table_round_trip() {
+        // random hands recorded from the table write, parse and replay unchanged
+        let mut rng = StdRng::seed_from_u64(21);
+        for betting in [
+            BettingStructure::NoLimit,
+            BettingStructure::PotLimit,
+            BettingStructure::FixedLimit {
+                small_bet: 10,
+                big_bet: 20,
+                cap: 4,
+            },
+        ] {
+            let config = TableConfig::new(5, 10).seats(6).ante(1).betting(betting);
+            let mut table = Table::with_rng(config, StdRng::seed_from_u64(4));
+            [500, 800, 0, 300, 1000, 700]
+                .into_iter()
+                .enumerate()
+                .for_each(|(seat, stack)| table.sit(seat, &format!("p{}", seat), stack).unwrap());
+            for id in 0..100 {
+                let Ok(mut events) = table.start_hand() else {
+                    break;
+                };
+                while let Some(seat) = table.to_act() {
+                    let actions = [
+                        Action::Fold,
+                        Action::Check,
+                        Action::Call,
+                        Action::Bet(rng.gen_range(1..400)),
+                        Action::Raise(rng.gen_range(1..800)),
+                        Action::AllIn,
+                    ];
+                    if let Ok(acted) = table.act(seat, *actions.choose(&mut rng).unwrap()) {
+                        events.extend(acted);
+                    }
+                }
+
+                let hand = HandHistory::from_events(id, "Replay", &table, &events);
+                let stacks = hand
+                    .seats
+                    .iter()
+                    .map(|seat| {
+                        let stack = table.seat(seat.seat - 1).unwrap().stack;
+                        (seat.name.clone(), stack)
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(hand.final_stacks(), stacks);
+                let written = hand.to_string();
+                assert_eq!(written.parse::<HandHistory>().unwrap(), hand, "{}", written);
+                assert_eq!(hand.replay().unwrap(), stacks, "{}", written);
+            }
+        }
+    }
+}
@@ -1 +1,15 @@
+pub mod betting;
+pub mod bot;
 pub mod coreutils;
+pub mod equity;
+pub mod hilo;
+pub mod history;
+pub mod pot;
+pub mod range;
+pub mod showdown;
+pub mod simulation;
+pub mod stats;
+pub mod table;
+
+#[cfg(test)]
+mod test_utils;
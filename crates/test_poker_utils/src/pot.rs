@@ -0,0 +1,306 @@
+use crate::{
+    coreutils::{Card, Variant},
+    showdown::{showdown_with, Contender, ShowdownError, ShowdownResult},
+};
+
+/// Chips a seat put in the pot over the whole hand
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Contribution {
+    pub seat: usize,
+    pub amount: u64,
+    pub folded: bool,
+}
+
+impl Contribution {
+    pub fn new(seat: usize, amount: u64) -> Contribution {
+        Contribution {
+            seat,
+            amount,
+            folded: false,
+        }
+    }
+
+    /// Dead money: the chips stay in the pot but the seat can't win them
+    pub fn folded(seat: usize, amount: u64) -> Contribution {
+        Contribution {
+            seat,
+            amount,
+            folded: true,
+        }
+    }
+}
+
+/// Main pot or side pot, with the seats that can win it
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Pot {
+    pub amount: u64,
+    pub eligible: Vec<usize>,
+}
+
+/// Split contributions into the main pot followed by the side pots
+///
+/// every distinct amount put in by a seat still in the hand closes a layer,
+/// each seat adds up to that amount and the seats which reached it are eligible.
+/// Dead money above the last layer goes to the last pot, and a last pot with a
+/// single eligible seat is an uncalled bet returned to it.
+pub fn side_pots(contributions: &[Contribution]) -> Vec<Pot> {
+    let mut levels = contributions
+        .iter()
+        .filter(|contribution| !contribution.folded)
+        .map(|contribution| contribution.amount)
+        .collect::<Vec<_>>();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let mut pots: Vec<Pot> = vec![];
+    let mut previous = 0;
+    for level in levels {
+        let amount = contributions
+            .iter()
+            .map(|contribution| contribution.amount.min(level) - contribution.amount.min(previous))
+            .sum();
+        let eligible = contributions
+            .iter()
+            .filter(|contribution| !contribution.folded && contribution.amount >= level)
+            .map(|contribution| contribution.seat)
+            .collect();
+        if amount > 0 {
+            pots.push(Pot { amount, eligible });
+        }
+        previous = level;
+    }
+
+    let dead = contributions
+        .iter()
+        .map(|contribution| contribution.amount.saturating_sub(previous))
+        .sum::<u64>();
+    if let Some(last) = pots.last_mut() {
+        last.amount += dead;
+    }
+
+    pots
+}
+
+/// Winner of one pot, showdown is None when a single seat is eligible
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PotAward {
+    pub pot: Pot,
+    pub showdown: Option<ShowdownResult>,
+}
+
+impl PotAward {
+    /// Chips of this pot won by the seat
+    pub fn amount(&self, seat: usize) -> u64 {
+        match &self.showdown {
+            Some(showdown) => showdown.amount(seat),
+            None if self.pot.eligible == [seat] => self.pot.amount,
+            None => 0,
+        }
+    }
+}
+
+/// Build the pots from contributions and resolve each one at showdown
+///
+/// contenders must hold the hole cards of every seat that didn't fold,
+/// pots with a single eligible seat are awarded without looking at the cards
+pub fn resolve_pots(
+    contributions: &[Contribution],
+    contenders: &[Contender],
+    board: &[Card],
+    button: usize,
+) -> Result<Vec<PotAward>, ShowdownError> {
+    resolve_pots_with(contributions, contenders, board, button, Variant::Standard)
+}
+
+/// Build and resolve the pots under the variant's rules
+pub fn resolve_pots_with(
+    contributions: &[Contribution],
+    contenders: &[Contender],
+    board: &[Card],
+    button: usize,
+    variant: Variant,
+) -> Result<Vec<PotAward>, ShowdownError> {
+    side_pots(contributions)
+        .into_iter()
+        .map(|pot| {
+            if pot.eligible.len() < 2 {
+                return Ok(PotAward {
+                    pot,
+                    showdown: None,
+                });
+            }
+            let eligible = pot
+                .eligible
+                .iter()
+                .map(|&seat| {
+                    contenders
+                        .iter()
+                        .find(|contender| contender.seat == seat)
+                        .copied()
+                        .ok_or(ShowdownError::MissingHand(seat))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let showdown = showdown_with(&eligible, board, pot.amount, button, variant)?;
+            Ok(PotAward {
+                pot,
+                showdown: Some(showdown),
+            })
+        })
+        .collect()
+}
+
+/// Total chips won by the seat over every pot
+pub fn total_won(awards: &[PotAward], seat: usize) -> u64 {
+    awards.iter().map(|award| award.amount(seat)).sum()
+}
+
+#[cfg(test)]
+mod pot_tests {
+    use super::{resolve_pots, side_pots, total_won, Contribution, Pot};
+    use crate::{
+        showdown::ShowdownError,
+        test_utils::{cards, contender},
+    };
+
+    fn pot(amount: u64, eligible: &[usize]) -> Pot {
+        Pot {
+            amount,
+            eligible: eligible.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_side_pots() {
+        let live = Contribution::new;
+        let folded = Contribution::folded;
+        [
+            // heads up, equal stacks
+            (vec![live(0, 100), live(1, 100)], vec![pot(200, &[0, 1])]),
+            // three all-ins for different amounts, the top 50 is uncalled
+            (
+                vec![live(0, 50), live(1, 100), live(2, 150)],
+                vec![pot(150, &[0, 1, 2]), pot(100, &[1, 2]), pot(50, &[2])],
+            ),
+            // the same stacks in another seat order
+            (
+                vec![live(0, 150), live(1, 50), live(2, 100)],
+                vec![pot(150, &[0, 1, 2]), pot(100, &[0, 2]), pot(50, &[0])],
+            ),
+            // two short stacks all-in for the same amount
+            (
+                vec![live(0, 50), live(1, 50), live(2, 100), live(3, 100)],
+                vec![pot(200, &[0, 1, 2, 3]), pot(100, &[2, 3])],
+            ),
+            // a folded blind is dead money in the main pot
+            (
+                vec![folded(0, 30), live(1, 100), live(2, 100)],
+                vec![pot(230, &[1, 2])],
+            ),
+            // a fold above the short all-in feeds both pots
+            (
+                vec![live(0, 50), folded(1, 80), live(2, 200), live(3, 200)],
+                vec![pot(200, &[0, 2, 3]), pot(330, &[2, 3])],
+            ),
+            // dead money above every live seat goes to the last pot
+            (
+                vec![live(0, 50), live(1, 50), folded(2, 100)],
+                vec![pot(200, &[0, 1])],
+            ),
+            // four all-ins, every layer different
+            (
+                vec![live(0, 10), live(1, 20), live(2, 30), live(3, 40)],
+                vec![
+                    pot(40, &[0, 1, 2, 3]),
+                    pot(30, &[1, 2, 3]),
+                    pot(20, &[2, 3]),
+                    pot(10, &[3]),
+                ],
+            ),
+            // a seat still in for nothing, e.g. everyone checked
+            (vec![live(0, 0), live(1, 0)], vec![]),
+            // everyone folded to the big blind
+            (vec![folded(0, 5), live(1, 10)], vec![pot(15, &[1])]),
+        ]
+        .into_iter()
+        .for_each(|(contributions, expect)| {
+            let pots = side_pots(&contributions);
+            assert_eq!(pots, expect, "{:?}", contributions);
+            assert_eq!(
+                pots.iter().map(|pot| pot.amount).sum::<u64>(),
+                contributions.iter().map(|c| c.amount).sum::<u64>()
+            );
+        });
+    }
+
+    #[test]
+    fn test_resolve_pots() {
+        let board = cards("Ks7d2c9hTs");
+        let contributions = [
+            Contribution::new(0, 50),
+            Contribution::new(1, 100),
+            Contribution::new(2, 150),
+            Contribution::folded(3, 20),
+        ];
+        // the short stack has the best hand, the middle stack the second best
+        let contenders = [
+            contender(0, "7s7h"),
+            contender(1, "KdQd"),
+            contender(2, "AsJc"),
+        ];
+        let awards = resolve_pots(&contributions, &contenders, &board, 3).unwrap();
+        assert_eq!(awards.len(), 3);
+        assert_eq!(total_won(&awards, 0), 170);
+        assert_eq!(total_won(&awards, 1), 100);
+        assert_eq!(total_won(&awards, 2), 50);
+        assert_eq!(total_won(&awards, 3), 0);
+        assert!(awards[2].showdown.is_none());
+
+        // the biggest stack wins everything
+        let contenders = [
+            contender(0, "AsQc"),
+            contender(1, "KdQd"),
+            contender(2, "7s7h"),
+        ];
+        let awards = resolve_pots(&contributions, &contenders, &board, 3).unwrap();
+        assert_eq!(total_won(&awards, 2), 320);
+    }
+
+    #[test]
+    fn test_resolve_split_side_pot() {
+        // the short stack wins the main pot, the side pot is split with an odd chip
+        let board = cards("AsKsQsJs2d");
+        let contributions = [
+            Contribution::new(0, 30),
+            Contribution::new(1, 71),
+            Contribution::new(2, 71),
+            Contribution::folded(3, 31),
+        ];
+        let contenders = [
+            contender(0, "Ts3c"),
+            contender(1, "Th4c"),
+            contender(2, "Td5c"),
+        ];
+        let awards = resolve_pots(&contributions, &contenders, &board, 1).unwrap();
+        assert_eq!(awards[0].pot, pot(120, &[0, 1, 2]));
+        assert_eq!(awards[1].pot, pot(83, &[1, 2]));
+        assert_eq!(total_won(&awards, 0), 120);
+        // seat 2 is left of the button
+        assert_eq!(total_won(&awards, 2), 42);
+        assert_eq!(total_won(&awards, 1), 41);
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let board = cards("Ks7d2c9hTs");
+        let contributions = [Contribution::new(0, 50), Contribution::new(1, 50)];
+        assert_eq!(
+            resolve_pots(&contributions, &[contender(0, "AsAd")], &board, 0),
+            Err(ShowdownError::MissingHand(1))
+        );
+
+        // uncontested pots don't need any card
+        let contributions = [Contribution::folded(0, 50), Contribution::new(1, 50)];
+        let awards = resolve_pots(&contributions, &[], &[], 0).unwrap();
+        assert_eq!(total_won(&awards, 1), 100);
+    }
+}
@@ -0,0 +1,473 @@
+use std::{fmt, str::FromStr};
+
+use crate::coreutils::{Card, Suits};
+
+const RANK_CHARS: &str = "23456789TJQKA";
+
+/// Two hole cards, the higher rank first (lower suit first for pairs)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Combo(pub [Card; 2]);
+
+impl Combo {
+    pub fn new(a: Card, b: Card) -> Combo {
+        if (b.rank(), a.suit as u8) > (a.rank(), b.suit as u8) {
+            Combo([b, a])
+        } else {
+            Combo([a, b])
+        }
+    }
+
+    pub fn cards(&self) -> [Card; 2] {
+        self.0
+    }
+
+    pub fn contains(&self, card: &Card) -> bool {
+        self.0.contains(card)
+    }
+
+    /// Whether the two combos share a card
+    pub fn conflicts(&self, other: &Combo) -> bool {
+        other.0.iter().any(|card| self.contains(card))
+    }
+}
+
+impl fmt::Display for Combo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.0[0], self.0[1])
+    }
+}
+
+/// Error for a range token that can't be parsed
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseRangeError {
+    pub token: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for ParseRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid range token `{}`: {}", self.token, self.reason)
+    }
+}
+
+impl std::error::Error for ParseRangeError {}
+
+/// Hand class like "TT", "AKs" or "AKo"
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Class {
+    Pair(u8),
+    Suited(u8, u8),
+    Offsuit(u8, u8),
+}
+
+impl Class {
+    fn combos(self) -> Vec<Combo> {
+        let card = |rank: u8, suit: Suits| Card::from(suit, if rank == 14 { 1 } else { rank });
+        let mut combos = vec![];
+        for (i, &s1) in Suits::ALL.iter().enumerate() {
+            for (j, &s2) in Suits::ALL.iter().enumerate() {
+                let combo = match self {
+                    Class::Pair(rank) if i < j => Combo::new(card(rank, s1), card(rank, s2)),
+                    Class::Suited(high, low) if i == j => Combo::new(card(high, s1), card(low, s2)),
+                    Class::Offsuit(high, low) if i != j => {
+                        Combo::new(card(high, s1), card(low, s2))
+                    }
+                    _ => continue,
+                };
+                combos.push(combo);
+            }
+        }
+        combos
+    }
+
+    fn with_kicker(self, kicker: u8) -> Class {
+        match self {
+            Class::Pair(_) => Class::Pair(kicker),
+            Class::Suited(high, _) => Class::Suited(high, kicker),
+            Class::Offsuit(high, _) => Class::Offsuit(high, kicker),
+        }
+    }
+
+    fn kicker(self) -> u8 {
+        match self {
+            Class::Pair(rank) => rank,
+            Class::Suited(_, low) | Class::Offsuit(_, low) => low,
+        }
+    }
+
+    /// Highest kicker the class can be extended to with "+"
+    fn top_kicker(self) -> u8 {
+        match self {
+            Class::Pair(_) => 14,
+            Class::Suited(high, _) | Class::Offsuit(high, _) => high - 1,
+        }
+    }
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Class::Pair(rank) => write!(f, "{}{}", rank_char(rank), rank_char(rank)),
+            Class::Suited(high, low) => write!(f, "{}{}s", rank_char(high), rank_char(low)),
+            Class::Offsuit(high, low) => write!(f, "{}{}o", rank_char(high), rank_char(low)),
+        }
+    }
+}
+
+/// Weighted set of hole card combos
+///
+/// Parsed from standard range notation, e.g. "TT+, AKs, A5s-A2s, KQo, AhKh:0.5",
+/// weights are in (0, 1] and default to 1.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Range {
+    combos: Vec<(Combo, f64)>,
+}
+
+impl Range {
+    pub fn new() -> Range {
+        Range::default()
+    }
+
+    /// Add a combo, replacing the weight when it is already in the range
+    pub fn insert(&mut self, combo: Combo, weight: f64) {
+        let combo = Combo::new(combo.0[0], combo.0[1]);
+        match self.combos.iter_mut().find(|(c, _)| *c == combo) {
+            Some(entry) => entry.1 = weight,
+            None => self.combos.push((combo, weight)),
+        }
+    }
+
+    pub fn weight(&self, combo: &Combo) -> Option<f64> {
+        let combo = Combo::new(combo.0[0], combo.0[1]);
+        self.combos
+            .iter()
+            .find(|(c, _)| *c == combo)
+            .map(|entry| entry.1)
+    }
+
+    /// Combos with their weights
+    pub fn combos(&self) -> &[(Combo, f64)] {
+        &self.combos
+    }
+
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// Drop every combo holding one of the dead cards
+    pub fn without(&self, dead: &[Card]) -> Range {
+        Range {
+            combos: self
+                .combos
+                .iter()
+                .filter(|(combo, _)| !dead.iter().any(|card| combo.contains(card)))
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+impl FromStr for Range {
+    type Err = ParseRangeError;
+
+    fn from_str(s: &str) -> Result<Range, ParseRangeError> {
+        let mut range = Range::new();
+        for token in s
+            .split(',')
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+        {
+            let error = |reason| ParseRangeError {
+                token: token.to_string(),
+                reason,
+            };
+            let (notation, weight) = match token.split_once(':') {
+                Some((notation, weight)) => {
+                    let weight = weight
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|_| error("weight is not a number"))?;
+                    if !(weight > 0.0 && weight <= 1.0) {
+                        return Err(error("weight must be in (0, 1]"));
+                    }
+                    (notation.trim(), weight)
+                }
+                None => (token, 1.0),
+            };
+
+            for combo in parse_notation(notation).map_err(error)? {
+                range.insert(combo, weight);
+            }
+        }
+
+        Ok(range)
+    }
+}
+
+impl fmt::Display for Range {
+    /// Compact canonical notation, full classes are grouped into spans
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = vec![];
+        let mut singles = vec![];
+
+        // pairs, then suited and offsuit grouped by the high card
+        let mut groups = vec![(2..=14).rev().map(Class::Pair).collect::<Vec<_>>()];
+        for high in (3..=14).rev() {
+            groups.push(
+                (2..high)
+                    .rev()
+                    .map(|low| Class::Suited(high, low))
+                    .collect(),
+            );
+        }
+        for high in (3..=14).rev() {
+            groups.push(
+                (2..high)
+                    .rev()
+                    .map(|low| Class::Offsuit(high, low))
+                    .collect(),
+            );
+        }
+
+        for group in groups {
+            // (class, weight) when the whole class is in with one weight
+            let mut run: Vec<(Class, f64)> = vec![];
+            for class in group {
+                let weights = class
+                    .combos()
+                    .into_iter()
+                    .map(|combo| (combo, self.weight(&combo)))
+                    .collect::<Vec<_>>();
+                let full = weights
+                    .iter()
+                    .all(|(_, weight)| weight.is_some() && *weight == weights[0].1);
+                if full {
+                    let weight = weights[0].1.unwrap();
+                    let continues = run.last().is_some_and(|&(last, w)| {
+                        w == weight && last.kicker() == class.kicker() + 1
+                    });
+                    if !continues {
+                        flush_run(&mut run, &mut parts);
+                    }
+                    run.push((class, weight));
+                } else {
+                    flush_run(&mut run, &mut parts);
+                    weights.iter().for_each(|&(combo, weight)| {
+                        if let Some(weight) = weight {
+                            singles.push(with_weight(combo.to_string(), weight));
+                        }
+                    });
+                }
+            }
+            flush_run(&mut run, &mut parts);
+        }
+
+        parts.extend(singles);
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Print a run of consecutive classes sharing one weight
+fn flush_run(run: &mut Vec<(Class, f64)>, parts: &mut Vec<String>) {
+    let (Some(&(top, weight)), Some(&(bottom, _))) = (run.first(), run.last()) else {
+        return;
+    };
+    let notation = if run.len() == 1 {
+        top.to_string()
+    } else if top.kicker() == top.top_kicker() {
+        format!("{}+", bottom)
+    } else {
+        format!("{}-{}", top, bottom)
+    };
+    parts.push(with_weight(notation, weight));
+    run.clear();
+}
+
+fn with_weight(notation: String, weight: f64) -> String {
+    if weight == 1.0 {
+        notation
+    } else {
+        format!("{}:{}", notation, weight)
+    }
+}
+
+fn rank_char(rank: u8) -> char {
+    RANK_CHARS.as_bytes()[rank as usize - 2] as char
+}
+
+fn parse_rank(c: char) -> Option<u8> {
+    RANK_CHARS.find(c).map(|i| i as u8 + 2)
+}
+
+/// Parse one token without its weight
+fn parse_notation(notation: &str) -> Result<Vec<Combo>, &'static str> {
+    if let Some((top, bottom)) = notation.split_once('-') {
+        let ((top, both), (bottom, _)) = (parse_class(top)?, parse_class(bottom)?);
+        let (high, low) = match (top, bottom) {
+            (Class::Pair(high), Class::Pair(low)) => (high, low),
+            (Class::Suited(a, high), Class::Suited(b, low))
+            | (Class::Offsuit(a, high), Class::Offsuit(b, low))
+                if a == b =>
+            {
+                (high, low)
+            }
+            _ => return Err("both ends of a span must be the same kind of hand"),
+        };
+        let (high, low) = (high.max(low), high.min(low));
+        return Ok((low..=high)
+            .flat_map(|kicker| expand(top.with_kicker(kicker), both))
+            .collect());
+    }
+
+    if let Some(base) = notation.strip_suffix('+') {
+        let (class, both) = parse_class(base)?;
+        return Ok((class.kicker()..=class.top_kicker())
+            .flat_map(|kicker| expand(class.with_kicker(kicker), both))
+            .collect());
+    }
+
+    if notation.len() == 4 {
+        if let (Some(a), Some(b)) = (notation.get(..2), notation.get(2..)) {
+            if let (Ok(a), Ok(b)) = (a.parse::<Card>(), b.parse::<Card>()) {
+                if a == b {
+                    return Err("a combo needs two different cards");
+                }
+                return Ok(vec![Combo::new(a, b)]);
+            }
+        }
+    }
+
+    let (class, both) = parse_class(notation)?;
+    Ok(expand(class, both))
+}
+
+/// Combos of a class, both suited and offsuit ones when `both` is set
+fn expand(class: Class, both: bool) -> Vec<Combo> {
+    match class {
+        Class::Offsuit(high, low) if both => {
+            let mut combos = Class::Suited(high, low).combos();
+            combos.extend(class.combos());
+            combos
+        }
+        _ => class.combos(),
+    }
+}
+
+/// Parse "TT", "AKs", "AKo" or "AK"
+///
+/// "AK" is returned as offsuit, with the flag set to also take suited combos
+fn parse_class(s: &str) -> Result<(Class, bool), &'static str> {
+    let mut chars = s.chars();
+    let (Some(a), Some(b)) = (chars.next(), chars.next()) else {
+        return Err("expect two ranks");
+    };
+    let (Some(a), Some(b)) = (parse_rank(a), parse_rank(b)) else {
+        return Err("unknown rank");
+    };
+    let (high, low) = (a.max(b), a.min(b));
+    match (chars.next(), chars.next()) {
+        (None, _) if high == low => Ok((Class::Pair(high), false)),
+        (None, _) => Ok((Class::Offsuit(high, low), true)),
+        (Some('s'), None) if high != low => Ok((Class::Suited(high, low), false)),
+        (Some('o'), None) if high != low => Ok((Class::Offsuit(high, low), false)),
+        _ => Err("unknown hand class"),
+    }
+}
+
+#[cfg(test)]
+mod range_tests {
+    use std::str::FromStr;
+
+    use super::{Combo, Range};
+    use crate::coreutils::Card;
+
+    fn combo(s: &str) -> Combo {
+        let cards = Card::from_strs(vec![&s[..2], &s[2..]]).unwrap();
+        Combo::new(cards[0], cards[1])
+    }
+
+    #[test]
+    fn test_parse_counts() {
+        [
+            ("AA", 6),
+            ("AKs", 4),
+            ("AKo", 12),
+            ("AK", 16),
+            ("AK-AJ", 48),
+            ("TT+", 30),
+            ("TT-77", 24),
+            ("77-TT", 24),
+            ("ATs+", 16),
+            ("A5s-A2s", 16),
+            ("KQo", 12),
+            ("AhKh", 1),
+            ("TT+, AKs, A5s-A2s, KQo", 62),
+            ("AKs, AhKh", 4),
+            ("22+, A2+, K2+", 6 * 13 + 16 * 23),
+        ]
+        .into_iter()
+        .for_each(|(s, expect)| {
+            assert_eq!(Range::from_str(s).unwrap().len(), expect, "{}", s);
+        });
+    }
+
+    #[test]
+    fn test_parse_weight() {
+        let range = Range::from_str("AKs:0.5, AhKh").unwrap();
+        assert_eq!(range.weight(&combo("AhKh")), Some(1.0));
+        assert_eq!(range.weight(&combo("KsAs")), Some(0.5));
+        assert_eq!(range.weight(&combo("AsKh")), None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        [
+            "AAs", "AKx", "A", "XX", "AK:2", "AK:x", "TT-AKs", "AKs-QJs", "AhAh", "🂡🂡",
+        ]
+        .into_iter()
+        .for_each(|s| {
+            assert!(Range::from_str(s).is_err(), "{}", s);
+        });
+    }
+
+    #[test]
+    fn test_without() {
+        let range = Range::from_str("AA, AKs").unwrap();
+        let dead = Card::from_strs(vec!["As", "Kh"]).unwrap();
+        let rest = range.without(&dead);
+        // AA loses 3 combos, AKs loses AsKs and AhKh
+        assert_eq!(rest.len(), 5);
+        assert!(rest
+            .combos()
+            .iter()
+            .all(|(combo, _)| !combo.contains(&dead[0]) && !combo.contains(&dead[1])));
+    }
+
+    #[test]
+    fn test_display() {
+        [
+            ("TT+, AKs, A5s-A2s, KQo", "TT+, AKs, A5s-A2s, KQo"),
+            ("AKs, AQs, AJs, KQo, QJo", "AJs+, KQo, QJo"),
+            ("88-66, AA", "AA, 88-66"),
+            ("AK", "AKs, AKo"),
+            ("AhKh, AA:0.5", "AA:0.5, AhKh"),
+            ("AKs, AsKs:0.25", "AsKs:0.25, AhKh, AdKd, AcKc"),
+            ("22+", "22+"),
+        ]
+        .into_iter()
+        .for_each(|(s, expect)| {
+            let range = Range::from_str(s).unwrap();
+            assert_eq!(range.to_string(), expect, "{}", s);
+
+            let reparsed = Range::from_str(&range.to_string()).unwrap();
+            assert_eq!(reparsed.len(), range.len());
+            range.combos().iter().for_each(|(combo, weight)| {
+                assert_eq!(reparsed.weight(combo), Some(*weight));
+            });
+        });
+    }
+}
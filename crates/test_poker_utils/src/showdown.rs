@@ -0,0 +1,323 @@
+use std::fmt;
+
+use crate::coreutils::{evaluate_best_with, BestHand, Card, CardSet, Variant};
+
+/// Errors when resolving a showdown
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ShowdownError {
+    /// At least one player must be in the showdown
+    NoPlayers,
+    /// A board has 3 to 5 cards
+    InvalidBoard(usize),
+    /// Two players sit at the same seat
+    DuplicateSeat(usize),
+    /// A card shows up twice across hands and board, or is not in the variant's deck
+    InvalidCard(Card),
+    /// A seat still in the hand has no hole cards to show
+    MissingHand(usize),
+}
+
+impl fmt::Display for ShowdownError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShowdownError::NoPlayers => write!(f, "no player in the showdown"),
+            ShowdownError::InvalidBoard(n) => write!(f, "board has {} cards, expected 3-5", n),
+            ShowdownError::DuplicateSeat(seat) => write!(f, "seat {} is taken twice", seat),
+            ShowdownError::InvalidCard(card) => write!(f, "card {} is duplicated or invalid", card),
+            ShowdownError::MissingHand(seat) => write!(f, "seat {} shows no hand", seat),
+        }
+    }
+}
+
+impl std::error::Error for ShowdownError {}
+
+/// Hole cards of a player who reached the showdown
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Contender {
+    pub seat: usize,
+    pub hole: [Card; 2],
+}
+
+impl Contender {
+    pub fn new(seat: usize, hole: [Card; 2]) -> Contender {
+        Contender { seat, hole }
+    }
+}
+
+/// Outcome of the showdown for one seat
+///
+/// `hand.value` displays the hand description, e.g. "Two pair, Aces and Kings"
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SeatResult {
+    pub seat: usize,
+    pub hand: BestHand,
+    pub winner: bool,
+    pub amount: u64,
+}
+
+/// Outcome of a showdown, one result per contender in the order given
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ShowdownResult {
+    pub results: Vec<SeatResult>,
+}
+
+impl ShowdownResult {
+    pub fn winners(&self) -> impl Iterator<Item = &SeatResult> {
+        self.results.iter().filter(|result| result.winner)
+    }
+
+    /// Chips won by the seat, 0 when the seat is not in the showdown
+    pub fn amount(&self, seat: usize) -> u64 {
+        self.results
+            .iter()
+            .find(|result| result.seat == seat)
+            .map_or(0, |result| result.amount)
+    }
+}
+
+/// Resolve a Hold'em showdown and split the pot between the best hands
+///
+/// tied hands share the pot evenly, the odd chips go one each to the tied
+/// players closest to the left of the button
+pub fn showdown(
+    contenders: &[Contender],
+    board: &[Card],
+    pot: u64,
+    button: usize,
+) -> Result<ShowdownResult, ShowdownError> {
+    showdown_with(contenders, board, pot, button, Variant::Standard)
+}
+
+/// Resolve a Hold'em showdown under the variant's rules
+pub fn showdown_with(
+    contenders: &[Contender],
+    board: &[Card],
+    pot: u64,
+    button: usize,
+    variant: Variant,
+) -> Result<ShowdownResult, ShowdownError> {
+    validate(contenders, board, variant)?;
+
+    let hands = contenders
+        .iter()
+        .map(|contender| {
+            let mut cards = board.to_vec();
+            cards.extend_from_slice(&contender.hole);
+            // validated: 5-7 cards
+            evaluate_best_with(&cards, variant).unwrap()
+        })
+        .collect::<Vec<_>>();
+    let best = hands.iter().map(|hand| hand.value).max().unwrap();
+    let seats = contenders
+        .iter()
+        .map(|contender| contender.seat)
+        .collect::<Vec<_>>();
+    let winners = odd_chip_order(&seats, button)
+        .into_iter()
+        .filter(|&i| hands[i].value == best)
+        .collect::<Vec<_>>();
+
+    let mut owed = vec![0; contenders.len()];
+    split_chips(pot, &winners, &mut owed);
+
+    Ok(ShowdownResult {
+        results: contenders
+            .iter()
+            .zip(hands)
+            .zip(owed)
+            .enumerate()
+            .map(|(i, ((contender, hand), amount))| SeatResult {
+                seat: contender.seat,
+                hand,
+                winner: winners.contains(&i),
+                amount,
+            })
+            .collect(),
+    })
+}
+
+fn validate(
+    contenders: &[Contender],
+    board: &[Card],
+    variant: Variant,
+) -> Result<(), ShowdownError> {
+    if contenders.is_empty() {
+        return Err(ShowdownError::NoPlayers);
+    }
+    if !(3..=5).contains(&board.len()) {
+        return Err(ShowdownError::InvalidBoard(board.len()));
+    }
+
+    let mut seen = CardSet::new();
+    let cards = contenders
+        .iter()
+        .flat_map(|contender| contender.hole)
+        .chain(board.iter().copied());
+    for card in cards {
+        if !variant.contains(&card) || seen.contains(card) {
+            return Err(ShowdownError::InvalidCard(card));
+        }
+        seen.insert(card);
+    }
+    for (i, contender) in contenders.iter().enumerate() {
+        if contenders[..i]
+            .iter()
+            .any(|other| other.seat == contender.seat)
+        {
+            return Err(ShowdownError::DuplicateSeat(contender.seat));
+        }
+    }
+
+    Ok(())
+}
+
+/// Indices of seats ordered clockwise starting from the seat left of the button
+pub(crate) fn odd_chip_order(seats: &[usize], button: usize) -> Vec<usize> {
+    let mut order = (0..seats.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| (seats[i] <= button, seats[i]));
+    order
+}
+
+/// Share amount evenly between the winners (indices into owed),
+/// the remainder goes one chip each to the first winners
+pub(crate) fn split_chips(amount: u64, winners: &[usize], owed: &mut [u64]) {
+    let n = winners.len() as u64;
+    if n == 0 {
+        return;
+    }
+    winners.iter().enumerate().for_each(|(i, &winner)| {
+        owed[winner] += amount / n + u64::from((i as u64) < amount % n);
+    });
+}
+
+#[cfg(test)]
+mod showdown_tests {
+    use super::{odd_chip_order, showdown, showdown_with, ShowdownError};
+    use crate::{
+        coreutils::{HandRank, Variant},
+        test_utils::{cards, contender},
+    };
+
+    #[test]
+    fn test_single_winner() {
+        let board = cards("Ks7d2c9hTs");
+        let players = [
+            contender(1, "AsAd"),
+            contender(4, "KdQc"),
+            contender(6, "7s7h"),
+        ];
+        let result = showdown(&players, &board, 300, 0).unwrap();
+        assert_eq!(
+            result.results.iter().map(|r| r.amount).collect::<Vec<_>>(),
+            [0, 0, 300]
+        );
+        let winner = result.winners().next().unwrap();
+        assert_eq!(winner.seat, 6);
+        assert_eq!(winner.hand.value.rank, HandRank::ThreeOfAKind);
+        assert_eq!(winner.hand.value.to_string(), "Three of a kind, Sevens");
+        assert_eq!(result.amount(6), 300);
+        assert_eq!(result.amount(5), 0);
+    }
+
+    #[test]
+    fn test_kickers() {
+        // same pair, the kicker decides
+        let board = cards("Ks7d2c9h3s");
+        let players = [contender(1, "KdQc"), contender(2, "KhJc")];
+        let result = showdown(&players, &board, 100, 0).unwrap();
+        assert_eq!(result.amount(1), 100);
+        assert_eq!(result.winners().count(), 1);
+
+        // the fifth card is on the board: kickers tie
+        let board = cards("KsQd9c8h7s");
+        let players = [contender(1, "Kd3c"), contender(2, "Kh2c")];
+        let result = showdown(&players, &board, 100, 0).unwrap();
+        assert_eq!(result.amount(1), 50);
+        assert_eq!(result.amount(2), 50);
+    }
+
+    #[test]
+    fn test_odd_chips() {
+        // the board plays for everyone
+        let board = cards("AsKsQsJsTs");
+        [
+            // button 0: seat 1 is first left of the button
+            (vec![1, 3, 5], 0, 100, vec![34, 33, 33]),
+            (vec![1, 3, 5], 0, 101, vec![34, 34, 33]),
+            // button 3: order 5, 1, 3
+            (vec![1, 3, 5], 3, 100, vec![33, 33, 34]),
+            (vec![1, 3, 5], 3, 101, vec![34, 33, 34]),
+            // the button itself is last
+            (vec![2, 7], 7, 5, vec![3, 2]),
+            (vec![2, 7], 2, 5, vec![2, 3]),
+        ]
+        .into_iter()
+        .for_each(|(seats, button, pot, expect)| {
+            let holes = ["2h3h", "2d3d", "2c3c"];
+            let players = seats
+                .iter()
+                .zip(holes)
+                .map(|(&seat, hole)| contender(seat, hole))
+                .collect::<Vec<_>>();
+            let result = showdown(&players, &board, pot, button).unwrap();
+            let amounts = result.results.iter().map(|r| r.amount).collect::<Vec<_>>();
+            assert_eq!(amounts, expect, "seats {:?} button {}", seats, button);
+            assert_eq!(result.winners().count(), seats.len());
+            assert_eq!(result.results[0].hand.value.to_string(), "Royal flush");
+        });
+    }
+
+    #[test]
+    fn test_odd_chip_order() {
+        assert_eq!(odd_chip_order(&[1, 3, 5, 8], 4), [2, 3, 0, 1]);
+        assert_eq!(odd_chip_order(&[8, 1, 5], 8), [1, 2, 0]);
+    }
+
+    #[test]
+    fn test_short_deck() {
+        // a flush beats a full house
+        let board = cards("AhKh7h7dKs");
+        let players = [contender(1, "7sKd"), contender(2, "9hTh")];
+        let result = showdown_with(&players, &board, 100, 0, Variant::ShortDeck).unwrap();
+        assert_eq!(result.amount(2), 100);
+        let result = showdown(&players, &board, 100, 0).unwrap();
+        assert_eq!(result.amount(1), 100);
+    }
+
+    #[test]
+    fn test_errors() {
+        let board = cards("Ks7d2c9hTs");
+        [
+            (vec![], board.clone(), ShowdownError::NoPlayers),
+            (
+                vec![contender(1, "AsAd")],
+                board[..2].to_vec(),
+                ShowdownError::InvalidBoard(2),
+            ),
+            (
+                vec![contender(1, "AsAd"), contender(2, "AsKd")],
+                board.clone(),
+                ShowdownError::InvalidCard(cards("As")[0]),
+            ),
+            (
+                vec![contender(1, "AsKs")],
+                board.clone(),
+                ShowdownError::InvalidCard(cards("Ks")[0]),
+            ),
+            (
+                vec![contender(1, "AsAd"), contender(1, "QsQd")],
+                board.clone(),
+                ShowdownError::DuplicateSeat(1),
+            ),
+        ]
+        .into_iter()
+        .for_each(|(players, board, err)| {
+            assert_eq!(showdown(&players, &board, 100, 0), Err(err));
+        });
+        let players = [contender(1, "2s3s"), contender(2, "AsAd")];
+        assert_eq!(
+            showdown_with(&players, &board[..3], 100, 0, Variant::ShortDeck),
+            Err(ShowdownError::InvalidCard(cards("2s")[0]))
+        );
+    }
+}
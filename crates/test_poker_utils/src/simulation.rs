@@ -0,0 +1,292 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    bot::{Observation, Player},
+    table::{Event, Table, TableConfig, TableError},
+};
+
+/// Results of one bot over a simulation
+#[derive(Debug, PartialEq, Clone)]
+pub struct BotResult {
+    pub name: String,
+    pub hands: u64,
+    /// Chips won, negative when lost
+    pub net: i64,
+    /// Actions the table refused, replaced by a check or a fold
+    pub illegal_actions: u64,
+    big_blind: u64,
+    // sum of the squared results of each hand, in big blinds
+    squares: f64,
+}
+
+impl BotResult {
+    fn new(name: &str, big_blind: u64) -> BotResult {
+        BotResult {
+            name: name.to_string(),
+            hands: 0,
+            net: 0,
+            illegal_actions: 0,
+            big_blind,
+            squares: 0.0,
+        }
+    }
+
+    fn record(&mut self, result: i64) {
+        let bb = result as f64 / self.big_blind as f64;
+        self.hands += 1;
+        self.net += result;
+        self.squares += bb * bb;
+    }
+
+    /// Win rate in big blinds per 100 hands
+    pub fn bb_per_100(&self) -> f64 {
+        self.mean() * 100.0
+    }
+
+    /// Normal approximation interval of bb_per_100, z = 1.96 for 95%
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        let n = self.hands as f64;
+        if n == 0.0 {
+            return (0.0, 0.0);
+        }
+        let mean = self.mean();
+        let variance = (self.squares / n - mean * mean).max(0.0);
+        let margin = z * (variance / n).sqrt();
+
+        ((mean - margin) * 100.0, (mean + margin) * 100.0)
+    }
+
+    fn mean(&self) -> f64 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+        self.net as f64 / self.big_blind as f64 / self.hands as f64
+    }
+}
+
+/// Outcome of a simulation, bots in seat order
+#[derive(Debug, PartialEq, Clone)]
+pub struct SimulationReport {
+    pub hands: u64,
+    pub bots: Vec<BotResult>,
+}
+
+/// Headless bot-vs-bot games through the table engine
+///
+/// Every bot starts each hand with the same stack, so results of all hands are
+/// independent samples. The seed drives the deck, the same bots replay the same games.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    config: TableConfig,
+    hands: u64,
+    stack: u64,
+    seed: u64,
+}
+
+impl Simulation {
+    /// 1000 hands with 100 big blind stacks
+    pub fn new(config: TableConfig) -> Simulation {
+        Simulation {
+            config,
+            hands: 1000,
+            stack: config.big_blind * 100,
+            seed: 0,
+        }
+    }
+
+    pub fn hands(mut self, hands: u64) -> Simulation {
+        self.hands = hands;
+        self
+    }
+
+    pub fn stack(mut self, stack: u64) -> Simulation {
+        self.stack = stack;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Simulation {
+        self.seed = seed;
+        self
+    }
+
+    /// Seat the bots in order and play every hand
+    pub fn run(&self, bots: &mut [Box<dyn Player>]) -> Result<SimulationReport, TableError> {
+        let config = self.config.seats(self.config.seats.max(bots.len()));
+        let mut table = Table::with_rng(config, StdRng::seed_from_u64(self.seed));
+        for (seat, bot) in bots.iter().enumerate() {
+            table.sit(seat, bot.name(), self.stack)?;
+        }
+        let mut results = bots
+            .iter()
+            .map(|bot| BotResult::new(bot.name(), config.big_blind))
+            .collect::<Vec<_>>();
+
+        for _ in 0..self.hands {
+            for seat in 0..bots.len() {
+                table.set_stack(seat, self.stack)?;
+            }
+            let events = table.start_hand()?;
+            notify(bots, &events);
+
+            while let Some(seat) = table.to_act() {
+                let observation = observe(&table, seat);
+                let action = bots[seat].act(&observation);
+                let events = match table.act(seat, action) {
+                    Ok(events) => events,
+                    Err(_) => {
+                        results[seat].illegal_actions += 1;
+                        table.act(seat, observation.check_or_fold())?
+                    }
+                };
+                notify(bots, &events);
+            }
+
+            for (seat, result) in results.iter_mut().enumerate() {
+                let stack = table.seat(seat).map_or(0, |seat| seat.stack);
+                result.record(stack as i64 - self.stack as i64);
+            }
+        }
+
+        Ok(SimulationReport {
+            hands: self.hands,
+            bots: results,
+        })
+    }
+}
+
+fn observe<R: Rng>(table: &Table<R>, seat: usize) -> Observation {
+    Observation {
+        seat,
+        // the seat to act is dealt in
+        hole: table.hole_cards(seat).unwrap(),
+        board: table.board().to_vec(),
+        street: table.street().unwrap(),
+        pot: table.pot(),
+        stack: table.seat(seat).map_or(0, |seat| seat.stack),
+        current_bet: table.current_bet(),
+        big_blind: table.config().big_blind,
+        legal: table.legal_actions().unwrap(),
+    }
+}
+
+/// Pass events to every bot, hole cards only to their owner
+fn notify(bots: &mut [Box<dyn Player>], events: &[Event]) {
+    for (seat, bot) in bots.iter_mut().enumerate() {
+        events
+            .iter()
+            .filter(
+                |event| !matches!(event, Event::HoleCards { seat: owner, .. } if *owner != seat),
+            )
+            .for_each(|event| bot.observe(event));
+    }
+}
+
+#[cfg(test)]
+mod simulation_tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::Simulation;
+    use crate::{
+        bot::{CallingStation, Observation, Player, RandomBot, TightAggressive},
+        table::{Action, Event, TableConfig},
+    };
+
+    /// Always raises to an amount out of range
+    struct Stubborn;
+
+    impl Player for Stubborn {
+        fn name(&self) -> &str {
+            "stubborn"
+        }
+
+        fn act(&mut self, _observation: &Observation) -> Action {
+            Action::Raise(u64::MAX)
+        }
+    }
+
+    /// Counts the hole cards it is shown
+    struct Watcher(Rc<Cell<usize>>);
+
+    impl Player for Watcher {
+        fn name(&self) -> &str {
+            "watcher"
+        }
+
+        fn act(&mut self, observation: &Observation) -> Action {
+            observation.check_or_fold()
+        }
+
+        fn observe(&mut self, event: &Event) {
+            if let Event::HoleCards { .. } = event {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+    }
+
+    fn bots() -> Vec<Box<dyn Player>> {
+        vec![
+            Box::new(TightAggressive::new()),
+            Box::new(CallingStation),
+            Box::new(RandomBot::new(3)),
+        ]
+    }
+
+    #[test]
+    fn test_seeded_runs_replay() {
+        let simulation = Simulation::new(TableConfig::new(1, 2)).hands(300).seed(9);
+        let report = simulation.run(&mut bots()).unwrap();
+        assert_eq!(report, simulation.run(&mut bots()).unwrap());
+        assert_eq!(report.hands, 300);
+        assert!(report.bots.iter().all(|bot| bot.hands == 300));
+        // chips only move between the bots
+        assert_eq!(report.bots.iter().map(|bot| bot.net).sum::<i64>(), 0);
+        assert!(report.bots.iter().all(|bot| bot.illegal_actions == 0));
+
+        let other = simulation.seed(10).run(&mut bots()).unwrap();
+        assert_ne!(report, other);
+    }
+
+    #[test]
+    fn test_tight_aggressive_beats_calling_station() {
+        let mut bots: Vec<Box<dyn Player>> =
+            vec![Box::new(TightAggressive::new()), Box::new(CallingStation)];
+        let report = Simulation::new(TableConfig::new(1, 2))
+            .hands(3000)
+            .seed(1)
+            .run(&mut bots)
+            .unwrap();
+        let tag = &report.bots[0];
+        assert_eq!(tag.name, "tight aggressive");
+        assert!(tag.bb_per_100() > 0.0, "{}", tag.bb_per_100());
+        // the win is significant at 95%
+        let (low, _) = tag.confidence_interval(1.96);
+        assert!(low > 0.0, "{}", low);
+        assert!((tag.bb_per_100() + report.bots[1].bb_per_100()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_illegal_actions() {
+        let mut bots: Vec<Box<dyn Player>> = vec![Box::new(Stubborn), Box::new(CallingStation)];
+        let report = Simulation::new(TableConfig::new(1, 2))
+            .hands(10)
+            .run(&mut bots)
+            .unwrap();
+        assert!(report.bots[0].illegal_actions >= 10);
+        assert_eq!(report.bots[1].illegal_actions, 0);
+    }
+
+    #[test]
+    fn test_bots_only_see_own_cards() {
+        let seen = Rc::new(Cell::new(0));
+        let mut bots: Vec<Box<dyn Player>> = vec![
+            Box::new(Watcher(seen.clone())),
+            Box::new(CallingStation),
+            Box::new(CallingStation),
+        ];
+        Simulation::new(TableConfig::new(1, 2))
+            .hands(5)
+            .run(&mut bots)
+            .unwrap();
+        assert_eq!(seen.get(), 5);
+    }
+}
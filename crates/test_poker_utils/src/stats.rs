@@ -0,0 +1,539 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{
+    betting::BettingStructure,
+    history::HandHistory,
+    table::{Action, Street},
+};
+
+/// Seat relative to the button, from the first to act preflop to the last
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Position {
+    /// Seats before the middle positions, under the gun first
+    Early,
+    /// The two seats before the cutoff, lojack and hijack
+    Middle,
+    Cutoff,
+    /// Heads-up the button posts the small blind but keeps this position
+    Button,
+    SmallBlind,
+    BigBlind,
+}
+
+impl Position {
+    /// Position of every player dealt in, in seat order
+    pub fn of(hand: &HandHistory) -> Vec<(String, Position)> {
+        let mut order = hand.players();
+        order.sort_by_key(|seat| (seat.seat <= hand.button, seat.seat));
+        let n = order.len();
+        let mut positions = order
+            .iter()
+            .enumerate()
+            .map(|(i, seat)| {
+                let position = match (n, i, n - 1 - i) {
+                    (2, 0, _) => Position::BigBlind,
+                    (_, _, 0) => Position::Button,
+                    (_, 0, _) => Position::SmallBlind,
+                    (_, 1, _) => Position::BigBlind,
+                    (_, _, 1) => Position::Cutoff,
+                    (_, _, 2 | 3) => Position::Middle,
+                    _ => Position::Early,
+                };
+                (seat.seat, seat.name.clone(), position)
+            })
+            .collect::<Vec<_>>();
+        positions.sort_unstable_by_key(|&(seat, ..)| seat);
+        positions
+            .into_iter()
+            .map(|(_, name, position)| (name, position))
+            .collect()
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Position::Early => "EP",
+            Position::Middle => "MP",
+            Position::Cutoff => "CO",
+            Position::Button => "BTN",
+            Position::SmallBlind => "SB",
+            Position::BigBlind => "BB",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Counters of a player over many hands, rates are derived from them
+///
+/// a chance counts the hands where the player could do it, e.g. three_bet_chances
+/// are the hands where the player acted facing a single preflop raise
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PlayerStats {
+    pub hands: u64,
+    /// Hands with chips put in preflop by a call, a bet or a raise, blinds aside
+    pub voluntary: u64,
+    pub preflop_raises: u64,
+    pub three_bets: u64,
+    pub three_bet_chances: u64,
+    pub folds_to_three_bet: u64,
+    pub fold_to_three_bet_chances: u64,
+    /// Bets and raises after the flop
+    pub aggressive_actions: u64,
+    /// Calls after the flop
+    pub calls: u64,
+    pub saw_flop: u64,
+    pub showdowns: u64,
+    pub won_at_showdown: u64,
+    /// Chips won in big blinds
+    pub net_big_blinds: f64,
+}
+
+impl PlayerStats {
+    /// Voluntarily put money in pot, percent of hands
+    pub fn vpip(&self) -> f64 {
+        percent(self.voluntary, self.hands)
+    }
+
+    /// Preflop raise, percent of hands
+    pub fn pfr(&self) -> f64 {
+        percent(self.preflop_raises, self.hands)
+    }
+
+    pub fn three_bet(&self) -> f64 {
+        percent(self.three_bets, self.three_bet_chances)
+    }
+
+    pub fn fold_to_three_bet(&self) -> f64 {
+        percent(self.folds_to_three_bet, self.fold_to_three_bet_chances)
+    }
+
+    /// Bets and raises per call after the flop, None without any call
+    pub fn aggression_factor(&self) -> Option<f64> {
+        (self.calls > 0).then(|| self.aggressive_actions as f64 / self.calls as f64)
+    }
+
+    /// Went to showdown, percent of the hands which saw the flop
+    pub fn wtsd(&self) -> f64 {
+        percent(self.showdowns, self.saw_flop)
+    }
+
+    /// Won money at showdown, percent of the showdowns
+    pub fn wsd(&self) -> f64 {
+        percent(self.won_at_showdown, self.showdowns)
+    }
+
+    pub fn bb_per_100(&self) -> f64 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+        self.net_big_blinds / self.hands as f64 * 100.0
+    }
+
+    fn merge(&mut self, other: &PlayerStats) {
+        self.hands += other.hands;
+        self.voluntary += other.voluntary;
+        self.preflop_raises += other.preflop_raises;
+        self.three_bets += other.three_bets;
+        self.three_bet_chances += other.three_bet_chances;
+        self.folds_to_three_bet += other.folds_to_three_bet;
+        self.fold_to_three_bet_chances += other.fold_to_three_bet_chances;
+        self.aggressive_actions += other.aggressive_actions;
+        self.calls += other.calls;
+        self.saw_flop += other.saw_flop;
+        self.showdowns += other.showdowns;
+        self.won_at_showdown += other.won_at_showdown;
+        self.net_big_blinds += other.net_big_blinds;
+    }
+}
+
+fn percent(count: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    count as f64 / total as f64 * 100.0
+}
+
+/// How the rows of a report are grouped
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum GroupBy {
+    Player,
+    /// Every player together, one row per position
+    Position,
+    PlayerAndPosition,
+}
+
+/// Stats of a group, player or position is None when the group spans all of them
+#[derive(Debug, PartialEq, Clone)]
+pub struct StatsRow {
+    pub player: Option<String>,
+    pub position: Option<Position>,
+    pub stats: PlayerStats,
+}
+
+/// Aggregates player stats over hand histories
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct StatsTracker {
+    stats: BTreeMap<(String, Position), PlayerStats>,
+}
+
+impl StatsTracker {
+    pub fn new() -> StatsTracker {
+        StatsTracker::default()
+    }
+
+    pub fn add_hands<'a>(&mut self, hands: impl IntoIterator<Item = &'a HandHistory>) {
+        hands.into_iter().for_each(|hand| self.add_hand(hand));
+    }
+
+    pub fn add_hand(&mut self, hand: &HandHistory) {
+        let big_blind = match hand.betting() {
+            // the big blind of limit games is the small bet
+            BettingStructure::FixedLimit { small_bet, .. } => small_bet,
+            _ => hand.big_blind,
+        };
+        let live = hand
+            .players()
+            .iter()
+            .filter(|seat| !folded(hand, &seat.name, Street::River))
+            .count();
+
+        // preflop bets and raises so far, the big blind aside
+        let mut raises = 0;
+        let mut opener = None;
+        let mut preflop: BTreeMap<&str, PlayerStats> = BTreeMap::new();
+        for action in hand
+            .actions
+            .iter()
+            .filter(|action| action.street == Street::Preflop)
+        {
+            let aggressive = matches!(
+                action.action,
+                Action::Bet(_) | Action::Raise(_) | Action::AllIn
+            );
+            let player = action.player.as_str();
+            let stats = preflop.entry(player).or_default();
+            stats.voluntary = u64::from(stats.voluntary > 0 || action.amount > 0);
+            if raises == 1 && stats.three_bet_chances == 0 && opener != Some(player) {
+                stats.three_bet_chances = 1;
+                stats.three_bets = u64::from(aggressive);
+            }
+            if raises == 2 && stats.fold_to_three_bet_chances == 0 && opener == Some(player) {
+                stats.fold_to_three_bet_chances = 1;
+                stats.folds_to_three_bet = u64::from(action.action == Action::Fold);
+            }
+            if aggressive {
+                stats.preflop_raises = 1;
+                raises += 1;
+                if raises == 1 {
+                    opener = Some(player);
+                }
+            }
+        }
+
+        for (name, position) in Position::of(hand) {
+            let mut stats = preflop.remove(name.as_str()).unwrap_or_default();
+            stats.hands = 1;
+            for action in hand
+                .actions
+                .iter()
+                .filter(|action| action.player == name && action.street != Street::Preflop)
+            {
+                match action.action {
+                    Action::Bet(_) | Action::Raise(_) | Action::AllIn => {
+                        stats.aggressive_actions += 1
+                    }
+                    Action::Call => stats.calls += 1,
+                    _ => {}
+                }
+            }
+            let saw_flop = hand.board.len() >= 3 && !folded(hand, &name, Street::Preflop);
+            let showdown = live > 1 && !folded(hand, &name, Street::River);
+            stats.saw_flop = u64::from(saw_flop);
+            stats.showdowns = u64::from(showdown);
+            stats.won_at_showdown = u64::from(showdown && hand.collected_by(&name) > 0);
+            stats.net_big_blinds = hand.net(&name) as f64 / big_blind as f64;
+
+            self.stats
+                .entry((name, position))
+                .or_default()
+                .merge(&stats);
+        }
+    }
+
+    /// Stats of the player over every position
+    pub fn player(&self, name: &str) -> Option<PlayerStats> {
+        self.rows(GroupBy::Player)
+            .into_iter()
+            .find(|row| row.player.as_deref() == Some(name))
+            .map(|row| row.stats)
+    }
+
+    /// Rows sorted by player name, then by position
+    pub fn rows(&self, group: GroupBy) -> Vec<StatsRow> {
+        let mut rows: BTreeMap<(Option<&str>, Option<Position>), PlayerStats> = BTreeMap::new();
+        for ((name, position), stats) in &self.stats {
+            let key = match group {
+                GroupBy::Player => (Some(name.as_str()), None),
+                GroupBy::Position => (None, Some(*position)),
+                GroupBy::PlayerAndPosition => (Some(name.as_str()), Some(*position)),
+            };
+            rows.entry(key).or_default().merge(stats);
+        }
+
+        rows.into_iter()
+            .map(|((player, position), stats)| StatsRow {
+                player: player.map(str::to_string),
+                position,
+                stats,
+            })
+            .collect()
+    }
+
+    /// One line per row after a header, rates in percent with one decimal
+    pub fn to_csv(&self, group: GroupBy) -> String {
+        let mut csv = String::from(
+            "player,position,hands,vpip,pfr,three_bet,fold_to_three_bet,\
+             aggression_factor,wtsd,wsd,bb_per_100\n",
+        );
+        for row in self.rows(group) {
+            let stats = &row.stats;
+            let fields = [
+                row.player.as_deref().map_or(String::new(), csv_field),
+                row.position.map_or(String::new(), |p| p.to_string()),
+                stats.hands.to_string(),
+                format!("{:.1}", stats.vpip()),
+                format!("{:.1}", stats.pfr()),
+                format!("{:.1}", stats.three_bet()),
+                format!("{:.1}", stats.fold_to_three_bet()),
+                stats
+                    .aggression_factor()
+                    .map_or(String::new(), |af| format!("{:.2}", af)),
+                format!("{:.1}", stats.wtsd()),
+                format!("{:.1}", stats.wsd()),
+                format!("{:.2}", stats.bb_per_100()),
+            ];
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Whether the player folded on the street or before
+fn folded(hand: &HandHistory, player: &str, street: Street) -> bool {
+    hand.actions.iter().any(|action| {
+        action.player == player && action.action == Action::Fold && action.street <= street
+    })
+}
+
+/// Quote names holding a comma, a quote or a line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use super::{GroupBy, Position, StatsTracker};
+    use crate::history::{parse_histories, HandHistory};
+
+    const HANDS: &str = r#"PokerStars Hand #1: Hold'em No Limit (5/10) - 2021/06/01 20:00:00 ET
+Table 'Stats' 6-max Seat #4 is the button
+Seat 1: Ann (1000 in chips)
+Seat 2: Bo (1000 in chips)
+Seat 3: Cy, Jr (1000 in chips)
+Seat 4: Di (1000 in chips)
+Ann: posts small blind 5
+Bo: posts big blind 10
+*** HOLE CARDS ***
+Cy, Jr: raises 20 to 30
+Di: raises 60 to 90
+Ann: folds
+Bo: folds
+Cy, Jr: folds
+Uncalled bet (60) returned to Di
+Di collected 75 from pot
+*** SUMMARY ***
+Total pot 75 | Rake 0
+
+PokerStars Hand #2: Hold'em No Limit (5/10) - 2021/06/01 20:01:00 ET
+Table 'Stats' 6-max Seat #1 is the button
+Seat 1: Ann (1000 in chips)
+Seat 2: Bo (1000 in chips)
+Seat 3: Cy, Jr (1000 in chips)
+Seat 4: Di (1000 in chips)
+Bo: posts small blind 5
+Cy, Jr: posts big blind 10
+*** HOLE CARDS ***
+Di: calls 10
+Ann: raises 30 to 40
+Bo: folds
+Cy, Jr: calls 30
+Di: calls 30
+*** FLOP *** [Ks 7d 2c]
+Cy, Jr: checks
+Di: bets 60
+Ann: raises 140 to 200
+Cy, Jr: folds
+Di: calls 140
+*** TURN *** [Ks 7d 2c] [9h]
+Di: checks
+Ann: bets 300
+Di: folds
+Uncalled bet (300) returned to Ann
+Ann collected 525 from pot
+*** SUMMARY ***
+Total pot 525 | Rake 0
+
+PokerStars Hand #3: Hold'em No Limit (5/10) - 2021/06/01 20:02:00 ET
+Table 'Stats' 6-max Seat #2 is the button
+Seat 1: Ann (1000 in chips)
+Seat 2: Bo (1000 in chips)
+Seat 3: Cy, Jr (1000 in chips)
+Seat 4: Di (1000 in chips)
+Cy, Jr: posts small blind 5
+Di: posts big blind 10
+*** HOLE CARDS ***
+Ann: calls 10
+Bo: calls 10
+Cy, Jr: calls 5
+Di: checks
+*** FLOP *** [Ks 7d 2c]
+Cy, Jr: checks
+Di: checks
+Ann: checks
+Bo: checks
+*** TURN *** [Ks 7d 2c] [9h]
+Cy, Jr: checks
+Di: checks
+Ann: checks
+Bo: checks
+*** RIVER *** [Ks 7d 2c 9h] [3s]
+Cy, Jr: checks
+Di: checks
+Ann: checks
+Bo: checks
+*** SHOW DOWN ***
+Ann: shows [Ah Kd] (a pair of Kings)
+Bo: shows [Qh Qd] (a pair of Queens)
+Cy, Jr: shows [Jh Jd] (a pair of Jacks)
+Di: shows [Th Td] (a pair of Tens)
+Ann collected 40 from pot
+*** SUMMARY ***
+Total pot 40 | Rake 0"#;
+
+    fn hands() -> Vec<HandHistory> {
+        parse_histories(HANDS)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    fn round(x: f64) -> f64 {
+        (x * 10.0).round() / 10.0
+    }
+
+    #[test]
+    fn test_positions() {
+        let hands = hands();
+        assert_eq!(
+            Position::of(&hands[0])
+                .into_iter()
+                .map(|(_, position)| position)
+                .collect::<Vec<_>>(),
+            [
+                Position::SmallBlind,
+                Position::BigBlind,
+                Position::Cutoff,
+                Position::Button
+            ]
+        );
+        let mut heads_up = hands[0].clone();
+        heads_up.seats.truncate(2);
+        heads_up.button = 2;
+        assert_eq!(
+            Position::of(&heads_up),
+            [
+                ("Ann".to_string(), Position::BigBlind),
+                ("Bo".to_string(), Position::Button)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_player_stats() {
+        let hands = hands();
+        // the fixtures are consistent hands
+        hands
+            .iter()
+            .for_each(|hand| assert_eq!(hand.replay().unwrap(), hand.final_stacks()));
+        let mut tracker = StatsTracker::new();
+        tracker.add_hands(&hands);
+
+        let ann = tracker.player("Ann").unwrap();
+        assert_eq!(ann.hands, 3);
+        assert_eq!(round(ann.vpip()), 66.7);
+        assert_eq!(round(ann.pfr()), 33.3);
+        assert_eq!(ann.three_bet_chances, 0);
+        assert_eq!(ann.aggression_factor(), None);
+        assert_eq!((ann.saw_flop, ann.showdowns), (2, 1));
+        assert_eq!((ann.wtsd(), ann.wsd()), (50.0, 100.0));
+        // -5, +285 and +30 chips at 10 a big blind
+        assert_eq!(round(ann.bb_per_100()), 1033.3);
+
+        let di = tracker.player("Di").unwrap();
+        assert_eq!((di.three_bets, di.three_bet_chances), (1, 2));
+        assert_eq!(di.three_bet(), 50.0);
+        assert_eq!(di.aggression_factor(), Some(1.0));
+        assert_eq!(round(di.vpip()), 66.7);
+
+        let cy = tracker.player("Cy, Jr").unwrap();
+        assert_eq!(cy.fold_to_three_bet(), 100.0);
+        assert_eq!(cy.three_bet_chances, 1);
+        let bo = tracker.player("Bo").unwrap();
+        assert_eq!((round(bo.vpip()), bo.wsd()), (33.3, 0.0));
+        assert_eq!(tracker.player("Zed"), None);
+
+        // every chip won was lost by someone
+        let total = tracker
+            .rows(GroupBy::Player)
+            .iter()
+            .map(|row| row.stats.net_big_blinds)
+            .sum::<f64>();
+        assert!(total.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_grouping_and_csv() {
+        let mut tracker = StatsTracker::new();
+        tracker.add_hands(&hands());
+        assert_eq!(tracker.rows(GroupBy::Player).len(), 4);
+        assert_eq!(tracker.rows(GroupBy::PlayerAndPosition).len(), 12);
+        let positions = tracker.rows(GroupBy::Position);
+        // four positions with four players, three hands each
+        assert_eq!(positions.len(), 4);
+        assert!(positions.iter().all(|row| row.stats.hands == 3));
+        let button = &positions[1];
+        assert_eq!(button.position, Some(Position::Button));
+        assert_eq!(button.stats.preflop_raises, 2);
+
+        let csv = tracker.to_csv(GroupBy::Player);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "player,position,hands,vpip,pfr,three_bet,fold_to_three_bet,\
+             aggression_factor,wtsd,wsd,bb_per_100"
+        );
+        assert_eq!(lines[1], "Ann,,3,66.7,33.3,0.0,0.0,,50.0,100.0,1033.33");
+        assert!(lines[3].starts_with("\"Cy, Jr\",,3,"));
+        assert_eq!(lines.len(), 5);
+        let csv = tracker.to_csv(GroupBy::PlayerAndPosition);
+        assert!(csv
+            .lines()
+            .any(|line| line.starts_with("Ann,BTN,1,100.0,100.0,")));
+    }
+}
@@ -0,0 +1,1369 @@
+use std::fmt;
+
+use rand::{rngs::ThreadRng, Rng};
+
+use crate::{
+    betting::{BettingSpot, BettingStructure},
+    coreutils::{evaluate_best, Card, CardDeck, HandValue},
+    pot::{resolve_pots, Contribution},
+    showdown::Contender,
+};
+
+/// Errors when seating players or acting at the table
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TableError {
+    /// The seat index is out of the table
+    InvalidSeat(usize),
+    /// Somebody already sits there
+    SeatTaken(usize),
+    /// Nobody sits there
+    SeatEmpty(usize),
+    /// At least two seats with chips are needed to start a hand
+    NotEnoughPlayers,
+    /// More seats with chips than one deck deals a hand to, see MAX_PLAYERS
+    TooManyPlayers,
+    /// Seats can't change and no hand can start while a hand is played
+    HandInProgress,
+    /// No hand is waiting for an action
+    NoHandInProgress,
+    /// It is not this seat's turn to act
+    OutOfTurn(usize),
+    /// The action is not allowed right now, e.g. a check facing a bet
+    IllegalAction(Action),
+    /// The bet or raise size is out of the legal range, amounts are street totals
+    InvalidAmount { amount: u64, min: u64, max: u64 },
+    /// Forced cards repeat, go to a seat not dealt in or make more than five board cards
+    InvalidDeal,
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::InvalidSeat(seat) => write!(f, "seat {} is out of the table", seat),
+            TableError::SeatTaken(seat) => write!(f, "seat {} is taken", seat),
+            TableError::SeatEmpty(seat) => write!(f, "seat {} is empty", seat),
+            TableError::NotEnoughPlayers => write!(f, "at least two players with chips are needed"),
+            TableError::TooManyPlayers => {
+                write!(f, "at most {} players can be dealt in", MAX_PLAYERS)
+            }
+            TableError::HandInProgress => write!(f, "a hand is in progress"),
+            TableError::NoHandInProgress => write!(f, "no hand is in progress"),
+            TableError::OutOfTurn(seat) => write!(f, "seat {} is not next to act", seat),
+            TableError::IllegalAction(action) => write!(f, "{:?} is not allowed now", action),
+            TableError::InvalidAmount { amount, min, max } => {
+                write!(f, "{} is out of the legal range {}-{}", amount, min, max)
+            }
+            TableError::InvalidDeal => write!(f, "forced cards don't fit the hand"),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+/// Most players one deck deals to: two hole cards each, three burns and five board cards
+pub const MAX_PLAYERS: usize = 22;
+
+/// Stakes, size and betting structure of a table
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TableConfig {
+    pub seats: usize,
+    pub small_blind: u64,
+    pub big_blind: u64,
+    pub ante: u64,
+    pub betting: BettingStructure,
+}
+
+impl TableConfig {
+    /// Nine handed No-Limit table without ante
+    pub fn new(small_blind: u64, big_blind: u64) -> TableConfig {
+        TableConfig {
+            seats: 9,
+            small_blind,
+            big_blind,
+            ante: 0,
+            betting: BettingStructure::NoLimit,
+        }
+    }
+
+    pub fn seats(mut self, seats: usize) -> TableConfig {
+        self.seats = seats;
+        self
+    }
+
+    pub fn ante(mut self, ante: u64) -> TableConfig {
+        self.ante = ante;
+        self
+    }
+
+    pub fn betting(mut self, betting: BettingStructure) -> TableConfig {
+        self.betting = betting;
+        self
+    }
+}
+
+/// A player sitting at the table
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Seat {
+    pub name: String,
+    pub stack: u64,
+}
+
+/// Betting rounds of a hand
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+/// A player's decision
+///
+/// Bet and Raise amounts are street totals ("raise to"), AllIn puts the whole stack in
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet(u64),
+    Raise(u64),
+    AllIn,
+}
+
+/// What the seat to act may do, bet and raise ranges are street totals (min, max)
+///
+/// a check is legal when call is 0, AllIn is legal when all_in is true
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LegalActions {
+    pub seat: usize,
+    /// Chips needed to call, capped by the stack
+    pub call: u64,
+    pub bet: Option<(u64, u64)>,
+    pub raise: Option<(u64, u64)>,
+    pub all_in: bool,
+}
+
+impl LegalActions {
+    pub fn can_check(&self) -> bool {
+        self.call == 0
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Blind {
+    Small,
+    Big,
+}
+
+/// Button and cards forced on the next hand, e.g. to replay a known hand
+///
+/// seats without forced hole cards and board cards past the forced ones come from the deck
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Deal {
+    pub button: Option<usize>,
+    pub hole: Vec<(usize, [Card; 2])>,
+    pub board: Vec<Card>,
+}
+
+/// What happened at the table, in order
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Event {
+    HandStarted {
+        button: usize,
+    },
+    AntePosted {
+        seat: usize,
+        amount: u64,
+    },
+    BlindPosted {
+        seat: usize,
+        blind: Blind,
+        amount: u64,
+    },
+    HoleCards {
+        seat: usize,
+        cards: [Card; 2],
+    },
+    /// action is resolved: an AllIn shows up as the Call, Bet or Raise it made,
+    /// amount is the chips put in by this action
+    Acted {
+        seat: usize,
+        action: Action,
+        amount: u64,
+        all_in: bool,
+    },
+    StreetDealt {
+        street: Street,
+        cards: Vec<Card>,
+    },
+    UncalledBet {
+        seat: usize,
+        amount: u64,
+    },
+    Shown {
+        seat: usize,
+        cards: [Card; 2],
+        hand: HandValue,
+    },
+    /// pot 0 is the main pot, side pots follow
+    PotAwarded {
+        seat: usize,
+        pot: usize,
+        amount: u64,
+    },
+    HandEnded,
+}
+
+/// A seat dealt into the current hand
+#[derive(Debug, Clone)]
+struct Player {
+    seat: usize,
+    hole: [Card; 2],
+    street_bet: u64,
+    total: u64,
+    folded: bool,
+    all_in: bool,
+    acted: bool,
+    // full bet level faced when last acting, a raise reopens once a full raise tops it
+    raise_level: u64,
+}
+
+#[derive(Debug, Clone)]
+struct HandState {
+    street: Street,
+    board: Vec<Card>,
+    // clockwise from the seat left of the button, the button is last
+    players: Vec<Player>,
+    to_act: Option<usize>,
+    current_bet: u64,
+    min_raise: u64,
+    // street total of the last full bet or raise
+    full_bet: u64,
+    // full bets and raises on this street, the big blind counts preflop
+    raises: u8,
+    // board cards dealt instead of the deck's
+    forced_board: Vec<Card>,
+}
+
+/// Hold'em table running one hand at a time, betting by the config's BettingStructure
+///
+/// A pure state machine: `start_hand` and `act` take the next input, update the
+/// table and return what happened as events. Generic over the random source
+/// like CardDeck, so a seeded rng replays the same hands.
+#[derive(Debug, Clone)]
+pub struct Table<R: Rng = ThreadRng> {
+    config: TableConfig,
+    seats: Vec<Option<Seat>>,
+    button: Option<usize>,
+    deck: CardDeck<R>,
+    hand: Option<HandState>,
+}
+
+impl Table {
+    /// Table dealing from the thread local rng
+    pub fn new(config: TableConfig) -> Table {
+        Table::with_rng(config, rand::thread_rng())
+    }
+}
+
+impl<R: Rng> Table<R> {
+    pub fn with_rng(config: TableConfig, rng: R) -> Table<R> {
+        Table {
+            config,
+            seats: vec![None; config.seats],
+            button: None,
+            deck: CardDeck::with_rng(rng),
+            hand: None,
+        }
+    }
+
+    pub fn config(&self) -> &TableConfig {
+        &self.config
+    }
+
+    pub fn sit(&mut self, seat: usize, name: &str, stack: u64) -> Result<(), TableError> {
+        self.check_between_hands()?;
+        match self.seats.get(seat) {
+            None => Err(TableError::InvalidSeat(seat)),
+            Some(Some(_)) => Err(TableError::SeatTaken(seat)),
+            Some(None) => {
+                self.seats[seat] = Some(Seat {
+                    name: name.to_string(),
+                    stack,
+                });
+                Ok(())
+            }
+        }
+    }
+
+    pub fn leave(&mut self, seat: usize) -> Result<Seat, TableError> {
+        self.check_between_hands()?;
+        self.seats
+            .get_mut(seat)
+            .ok_or(TableError::InvalidSeat(seat))?
+            .take()
+            .ok_or(TableError::SeatEmpty(seat))
+    }
+
+    /// Rebuy or top up between hands
+    pub fn set_stack(&mut self, seat: usize, stack: u64) -> Result<(), TableError> {
+        self.check_between_hands()?;
+        self.seats
+            .get_mut(seat)
+            .ok_or(TableError::InvalidSeat(seat))?
+            .as_mut()
+            .ok_or(TableError::SeatEmpty(seat))?
+            .stack = stack;
+        Ok(())
+    }
+
+    pub fn seat(&self, seat: usize) -> Option<&Seat> {
+        self.seats.get(seat)?.as_ref()
+    }
+
+    /// Button of the current or last hand
+    pub fn button(&self) -> Option<usize> {
+        self.button
+    }
+
+    /// Seat expected to act, None between hands
+    pub fn to_act(&self) -> Option<usize> {
+        let hand = self.hand.as_ref()?;
+        hand.to_act.map(|i| hand.players[i].seat)
+    }
+
+    pub fn is_hand_over(&self) -> bool {
+        self.to_act().is_none()
+    }
+
+    /// Street of the current or last hand
+    pub fn street(&self) -> Option<Street> {
+        self.hand.as_ref().map(|hand| hand.street)
+    }
+
+    pub fn board(&self) -> &[Card] {
+        self.hand.as_ref().map_or(&[], |hand| &hand.board)
+    }
+
+    pub fn hole_cards(&self, seat: usize) -> Option<[Card; 2]> {
+        self.player(seat).map(|player| player.hole)
+    }
+
+    /// Chips in the middle, antes and bets of the current street included
+    pub fn pot(&self) -> u64 {
+        self.hand.as_ref().map_or(0, |hand| {
+            hand.players.iter().map(|player| player.total).sum()
+        })
+    }
+
+    /// Highest street total to match
+    pub fn current_bet(&self) -> u64 {
+        self.hand.as_ref().map_or(0, |hand| hand.current_bet)
+    }
+
+    /// Chips the seat put in on this street
+    pub fn street_bet(&self, seat: usize) -> u64 {
+        self.player(seat).map_or(0, |player| player.street_bet)
+    }
+
+    /// Whether the seat is dealt in and has not folded
+    pub fn is_live(&self, seat: usize) -> bool {
+        self.player(seat).is_some_and(|player| !player.folded)
+    }
+
+    /// Move the button, post antes and blinds and deal the hole cards
+    pub fn start_hand(&mut self) -> Result<Vec<Event>, TableError> {
+        self.start_hand_with(&Deal::default())
+    }
+
+    /// Start a hand with the button and cards of the deal
+    ///
+    /// the button may be an empty seat, like a dead button
+    pub fn start_hand_with(&mut self, deal: &Deal) -> Result<Vec<Event>, TableError> {
+        self.check_between_hands()?;
+        let active = (0..self.seats.len())
+            .filter(|&seat| self.stack(seat) > 0)
+            .collect::<Vec<_>>();
+        if active.len() < 2 {
+            return Err(TableError::NotEnoughPlayers);
+        }
+        if active.len() > MAX_PLAYERS {
+            return Err(TableError::TooManyPlayers);
+        }
+
+        let button = match (deal.button, self.button) {
+            (Some(button), _) if button >= self.seats.len() => {
+                return Err(TableError::InvalidSeat(button))
+            }
+            (Some(button), _) => button,
+            (None, Some(button)) => *active
+                .iter()
+                .find(|&&seat| seat > button)
+                .unwrap_or(&active[0]),
+            (None, None) => active[0],
+        };
+        let forced = deal
+            .hole
+            .iter()
+            .flat_map(|(_, hole)| hole)
+            .chain(&deal.board)
+            .copied()
+            .collect::<Vec<_>>();
+        if deal.board.len() > 5 || deal.hole.iter().any(|(seat, _)| !active.contains(seat)) {
+            return Err(TableError::InvalidDeal);
+        }
+        self.deck.reset();
+        self.deck
+            .remove_all(&forced)
+            .map_err(|_| TableError::InvalidDeal)?;
+        self.button = Some(button);
+
+        let mut order = active;
+        order.sort_by_key(|&seat| (seat <= button, seat));
+        let n = order.len();
+        let forced_hole = |seat| {
+            deal.hole
+                .iter()
+                .find(|(forced, _)| *forced == seat)
+                .map(|&(_, hole)| hole)
+        };
+        // one card at a time around the table, to the seats without forced cards
+        let random = order
+            .iter()
+            .filter(|&&seat| forced_hole(seat).is_none())
+            .count();
+        let dealt = self
+            .deck
+            .deal_n(2 * random)
+            .ok_or(TableError::TooManyPlayers)?;
+        let mut next = 0;
+        let mut players = order
+            .iter()
+            .map(|&seat| Player {
+                seat,
+                hole: forced_hole(seat).unwrap_or_else(|| {
+                    next += 1;
+                    [dealt[next - 1], dealt[next - 1 + random]]
+                }),
+                street_bet: 0,
+                total: 0,
+                folded: false,
+                all_in: false,
+                acted: false,
+                raise_level: 0,
+            })
+            .collect::<Vec<_>>();
+        let mut events = vec![Event::HandStarted { button }];
+
+        if self.config.ante > 0 {
+            for player in players.iter_mut() {
+                let amount = self.post(player, self.config.ante);
+                player.total += amount;
+                events.push(Event::AntePosted {
+                    seat: player.seat,
+                    amount,
+                });
+            }
+        }
+
+        let (small, big) = if players.len() == 2 { (1, 0) } else { (0, 1) };
+        for (i, blind, size) in [
+            (small, Blind::Small, self.config.small_blind),
+            (big, Blind::Big, self.config.big_blind),
+        ] {
+            let player = &mut players[i];
+            let amount = self.post(player, size);
+            player.street_bet += amount;
+            player.total += amount;
+            events.push(Event::BlindPosted {
+                seat: player.seat,
+                blind,
+                amount,
+            });
+        }
+
+        events.extend(players.iter().map(|player| Event::HoleCards {
+            seat: player.seat,
+            cards: player.hole,
+        }));
+
+        self.hand = Some(HandState {
+            street: Street::Preflop,
+            board: vec![],
+            players,
+            to_act: None,
+            current_bet: self.config.big_blind,
+            min_raise: self
+                .config
+                .betting
+                .opening_size(Street::Preflop, self.config.big_blind),
+            full_bet: self.config.big_blind,
+            raises: 1,
+            forced_board: deal.board.clone(),
+        });
+        let first = (big + 1) % n;
+        self.advance(first, &mut events)?;
+
+        Ok(events)
+    }
+
+    /// Apply the action of the seat expected to act
+    pub fn act(&mut self, seat: usize, action: Action) -> Result<Vec<Event>, TableError> {
+        let hand = self.hand.as_ref().ok_or(TableError::NoHandInProgress)?;
+        let i = hand.to_act.ok_or(TableError::NoHandInProgress)?;
+        if hand.players[i].seat != seat {
+            return Err(TableError::OutOfTurn(seat));
+        }
+
+        let player = &hand.players[i];
+        // to_act is set: legal actions exist
+        let legal = self.legal_actions().unwrap();
+        let target = match action {
+            Action::Fold => None,
+            Action::Check if legal.can_check() => None,
+            Action::Call if !legal.can_check() => Some(player.street_bet + legal.call),
+            Action::Bet(amount) if legal.bet.is_some() => {
+                let (min, max) = legal.bet.unwrap();
+                check_amount(amount, min, max)?;
+                Some(amount)
+            }
+            Action::Raise(amount) if legal.raise.is_some() => {
+                let (min, max) = legal.raise.unwrap();
+                check_amount(amount, min, max)?;
+                Some(amount)
+            }
+            Action::AllIn if legal.all_in => Some(player.street_bet + self.stack(seat)),
+            _ => return Err(TableError::IllegalAction(action)),
+        };
+
+        let mut events = vec![];
+        let resolved = match target {
+            None if action == Action::Fold => {
+                self.hand.as_mut().unwrap().players[i].folded = true;
+                Action::Fold
+            }
+            None => Action::Check,
+            Some(to) => {
+                let resolved = if to <= hand.current_bet {
+                    Action::Call
+                } else if hand.current_bet == 0 {
+                    Action::Bet(to)
+                } else {
+                    Action::Raise(to)
+                };
+                let amount = to - player.street_bet;
+                self.seats[seat].as_mut().unwrap().stack -= amount;
+                let all_in = self.stack(seat) == 0;
+
+                let hand = self.hand.as_mut().unwrap();
+                if to > hand.current_bet {
+                    let increment = to - hand.current_bet;
+                    // a short all-in neither reopens the betting nor counts toward the cap
+                    if increment >= hand.min_raise {
+                        hand.raises = hand.raises.saturating_add(1);
+                        hand.min_raise = increment;
+                        hand.full_bet = to;
+                    }
+                    hand.current_bet = to;
+                }
+                let player = &mut hand.players[i];
+                player.street_bet = to;
+                player.total += amount;
+                player.all_in = all_in;
+                events.push(Event::Acted {
+                    seat,
+                    action: resolved,
+                    amount,
+                    all_in,
+                });
+                resolved
+            }
+        };
+        if target.is_none() {
+            events.push(Event::Acted {
+                seat,
+                action: resolved,
+                amount: 0,
+                all_in: false,
+            });
+        }
+
+        let hand = self.hand.as_mut().unwrap();
+        hand.players[i].acted = true;
+        hand.players[i].raise_level = hand.full_bet;
+        let next = (i + 1) % hand.players.len();
+        self.advance(next, &mut events)?;
+
+        Ok(events)
+    }
+
+    /// Actions open to the seat to act, None between hands
+    pub fn legal_actions(&self) -> Option<LegalActions> {
+        let hand = self.hand.as_ref()?;
+        let player = &hand.players[hand.to_act?];
+        let stack = self.stack(player.seat);
+        let all_in = player.street_bet + stack;
+        let call = hand
+            .current_bet
+            .saturating_sub(player.street_bet)
+            .min(stack);
+
+        // a short all-in doesn't reopen the betting to players who already acted
+        let reopened = !player.acted || hand.full_bet > player.raise_level;
+        let range = reopened
+            .then(|| {
+                self.config.betting.raise_range(&BettingSpot {
+                    street: hand.street,
+                    big_blind: self.config.big_blind,
+                    pot: self.pot(),
+                    current_bet: hand.current_bet,
+                    last_raise: hand.min_raise,
+                    street_bet: player.street_bet,
+                    stack,
+                    raises: hand.raises,
+                })
+            })
+            .flatten();
+        let (bet, raise) = match hand.current_bet {
+            0 => (range, None),
+            _ => (None, range),
+        };
+
+        Some(LegalActions {
+            seat: player.seat,
+            call,
+            bet,
+            raise,
+            all_in: all_in <= hand.current_bet || range.is_some_and(|(_, max)| max == all_in),
+        })
+    }
+
+    fn player(&self, seat: usize) -> Option<&Player> {
+        self.hand
+            .as_ref()?
+            .players
+            .iter()
+            .find(|player| player.seat == seat)
+    }
+
+    fn stack(&self, seat: usize) -> u64 {
+        self.seat(seat).map_or(0, |seat| seat.stack)
+    }
+
+    fn check_between_hands(&self) -> Result<(), TableError> {
+        match self.to_act() {
+            Some(_) => Err(TableError::HandInProgress),
+            None => Ok(()),
+        }
+    }
+
+    /// Take up to amount from the player's stack, a short stack goes all-in
+    fn post(&mut self, player: &mut Player, amount: u64) -> u64 {
+        let seat = self.seats[player.seat].as_mut().unwrap();
+        let amount = amount.min(seat.stack);
+        seat.stack -= amount;
+        player.all_in = seat.stack == 0;
+        amount
+    }
+
+    /// Give the turn to the next player from index start, or close the betting round
+    fn advance(&mut self, mut start: usize, events: &mut Vec<Event>) -> Result<(), TableError> {
+        loop {
+            let hand = self.hand.as_mut().unwrap();
+            hand.to_act = next_to_act(hand, start);
+            if hand.to_act.is_some() {
+                return Ok(());
+            }
+
+            let live = hand.players.iter().filter(|player| !player.folded).count();
+            if live == 1 || hand.street == Street::River {
+                self.finish(events);
+                return Ok(());
+            }
+
+            let (street, count) = match hand.street {
+                Street::Preflop => (Street::Flop, 3),
+                Street::Flop => (Street::Turn, 1),
+                _ => (Street::River, 1),
+            };
+            self.deck.burn();
+            let mut cards = hand
+                .forced_board
+                .iter()
+                .skip(hand.board.len())
+                .take(count)
+                .copied()
+                .collect::<Vec<_>>();
+            // MAX_PLAYERS leaves enough cards for the board
+            let dealt = self
+                .deck
+                .deal_n(count - cards.len())
+                .ok_or(TableError::TooManyPlayers)?;
+            cards.extend(dealt);
+            hand.street = street;
+            hand.board.extend_from_slice(&cards);
+            hand.current_bet = 0;
+            hand.min_raise = self
+                .config
+                .betting
+                .opening_size(street, self.config.big_blind);
+            hand.full_bet = 0;
+            hand.raises = 0;
+            hand.players.iter_mut().for_each(|player| {
+                player.street_bet = 0;
+                player.acted = false;
+                player.raise_level = 0;
+            });
+            events.push(Event::StreetDealt { street, cards });
+            // postflop the seat left of the button acts first
+            start = 0;
+        }
+    }
+
+    /// Return the uncalled bet, then award every pot
+    fn finish(&mut self, events: &mut Vec<Event>) {
+        let button = self.button.unwrap();
+        let hand = self.hand.as_mut().unwrap();
+
+        let mut totals = hand
+            .players
+            .iter()
+            .enumerate()
+            .map(|(i, player)| (player.total, i))
+            .collect::<Vec<_>>();
+        totals.sort_unstable_by_key(|&(total, _)| std::cmp::Reverse(total));
+        let (top, i) = totals[0];
+        let uncalled = top - totals[1].0;
+        if uncalled > 0 {
+            let player = &mut hand.players[i];
+            player.total -= uncalled;
+            self.seats[player.seat].as_mut().unwrap().stack += uncalled;
+            events.push(Event::UncalledBet {
+                seat: player.seat,
+                amount: uncalled,
+            });
+        }
+
+        let contributions = hand
+            .players
+            .iter()
+            .map(|player| Contribution {
+                seat: player.seat,
+                amount: player.total,
+                folded: player.folded,
+            })
+            .collect::<Vec<_>>();
+        let contenders = hand
+            .players
+            .iter()
+            .filter(|player| !player.folded)
+            .map(|player| Contender::new(player.seat, player.hole))
+            .collect::<Vec<_>>();
+        if contenders.len() > 1 {
+            for contender in &contenders {
+                let mut cards = hand.board.clone();
+                cards.extend_from_slice(&contender.hole);
+                events.push(Event::Shown {
+                    seat: contender.seat,
+                    cards: contender.hole,
+                    // five board cards at showdown
+                    hand: evaluate_best(&cards).unwrap().value,
+                });
+            }
+        }
+
+        // a single contender needs no board, several see the river
+        let awards = resolve_pots(&contributions, &contenders, &hand.board, button).unwrap();
+        for (pot, award) in awards.iter().enumerate() {
+            for &seat in &award.pot.eligible {
+                let amount = award.amount(seat);
+                if amount > 0 {
+                    self.seats[seat].as_mut().unwrap().stack += amount;
+                    events.push(Event::PotAwarded { seat, pot, amount });
+                }
+            }
+        }
+
+        hand.to_act = None;
+        events.push(Event::HandEnded);
+    }
+}
+
+fn check_amount(amount: u64, min: u64, max: u64) -> Result<(), TableError> {
+    if (min..=max).contains(&amount) {
+        Ok(())
+    } else {
+        Err(TableError::InvalidAmount { amount, min, max })
+    }
+}
+
+/// Index of the first player from start who still has to act, None when the round is closed
+fn next_to_act(hand: &HandState, start: usize) -> Option<usize> {
+    let n = hand.players.len();
+    let able = hand
+        .players
+        .iter()
+        .filter(|player| !player.folded && !player.all_in)
+        .collect::<Vec<_>>();
+    let live = hand.players.iter().filter(|player| !player.folded).count();
+    // nobody left to bet against
+    if live < 2 || able.is_empty() {
+        return None;
+    }
+    if able.len() == 1 && able[0].street_bet >= hand.current_bet {
+        return None;
+    }
+
+    (start..start + n).map(|i| i % n).find(|&i| {
+        let player = &hand.players[i];
+        !player.folded && !player.all_in && (!player.acted || player.street_bet < hand.current_bet)
+    })
+}
+
+#[cfg(test)]
+mod table_tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    use super::{
+        Action, Blind, Deal, Event, LegalActions, Street, Table, TableConfig, TableError,
+        MAX_PLAYERS,
+    };
+    use crate::{
+        betting::BettingStructure,
+        coreutils::{Card, CardSet},
+    };
+
+    fn table(stacks: &[u64]) -> Table<StdRng> {
+        let mut table = Table::with_rng(
+            TableConfig::new(5, 10).seats(stacks.len()),
+            StdRng::seed_from_u64(1),
+        );
+        stacks.iter().enumerate().for_each(|(seat, &stack)| {
+            table.sit(seat, &format!("p{}", seat), stack).unwrap();
+        });
+        table
+    }
+
+    fn stacks<R: Rng>(table: &Table<R>, n: usize) -> Vec<u64> {
+        (0..n).map(|seat| table.seat(seat).unwrap().stack).collect()
+    }
+
+    #[test]
+    fn test_blinds_and_button() {
+        let mut table = table(&[1000, 1000, 1000]);
+        let events = table.start_hand().unwrap();
+        assert_eq!(events[0], Event::HandStarted { button: 0 });
+        assert_eq!(
+            events[1],
+            Event::BlindPosted {
+                seat: 1,
+                blind: Blind::Small,
+                amount: 5
+            }
+        );
+        assert_eq!(
+            events[2],
+            Event::BlindPosted {
+                seat: 2,
+                blind: Blind::Big,
+                amount: 10
+            }
+        );
+        assert_eq!(table.to_act(), Some(0));
+        assert_eq!(table.pot(), 15);
+
+        // fold around: the big blind gets the small blind, the rest is uncalled
+        table.act(0, Action::Fold).unwrap();
+        let events = table.act(1, Action::Fold).unwrap();
+        assert!(events.contains(&Event::UncalledBet { seat: 2, amount: 5 }));
+        assert!(events.contains(&Event::PotAwarded {
+            seat: 2,
+            pot: 0,
+            amount: 10
+        }));
+        assert_eq!(events.last(), Some(&Event::HandEnded));
+        assert_eq!(stacks(&table, 3), [1000, 995, 1005]);
+        assert!(table.is_hand_over());
+
+        let events = table.start_hand().unwrap();
+        assert_eq!(events[0], Event::HandStarted { button: 1 });
+        assert_eq!(table.to_act(), Some(1));
+        table.start_hand().unwrap_err();
+    }
+
+    #[test]
+    fn test_heads_up() {
+        // the button posts the small blind, acts first preflop and last after
+        let mut table = table(&[1000, 1000]);
+        let events = table.start_hand().unwrap();
+        assert_eq!(events[0], Event::HandStarted { button: 0 });
+        assert!(events.contains(&Event::BlindPosted {
+            seat: 0,
+            blind: Blind::Small,
+            amount: 5
+        }));
+        assert_eq!(table.to_act(), Some(0));
+        table.act(0, Action::Call).unwrap();
+        // the big blind has the option
+        assert_eq!(table.to_act(), Some(1));
+        let events = table.act(1, Action::Check).unwrap();
+        assert!(matches!(
+            events.last(),
+            Some(Event::StreetDealt {
+                street: Street::Flop,
+                ..
+            })
+        ));
+        assert_eq!(table.board().len(), 3);
+        assert_eq!(table.to_act(), Some(1));
+    }
+
+    #[test]
+    fn test_illegal_actions() {
+        let mut table = table(&[1000, 1000, 1000]);
+        assert_eq!(
+            table.act(0, Action::Check),
+            Err(TableError::NoHandInProgress)
+        );
+        table.start_hand().unwrap();
+        assert_eq!(table.act(1, Action::Fold), Err(TableError::OutOfTurn(1)));
+        assert_eq!(
+            table.act(0, Action::Check),
+            Err(TableError::IllegalAction(Action::Check))
+        );
+        assert_eq!(
+            table.act(0, Action::Bet(20)),
+            Err(TableError::IllegalAction(Action::Bet(20)))
+        );
+        assert_eq!(
+            table.act(0, Action::Raise(15)),
+            Err(TableError::InvalidAmount {
+                amount: 15,
+                min: 20,
+                max: 1000
+            })
+        );
+        assert_eq!(
+            table.act(0, Action::Raise(1001)),
+            Err(TableError::InvalidAmount {
+                amount: 1001,
+                min: 20,
+                max: 1000
+            })
+        );
+        assert_eq!(table.sit(0, "x", 100), Err(TableError::HandInProgress));
+    }
+
+    #[test]
+    fn test_min_raise() {
+        let mut table = table(&[1000, 1000, 1000]);
+        table.start_hand().unwrap();
+        // raise by 25 to 35, the next raise is at least 25 more
+        table.act(0, Action::Raise(35)).unwrap();
+        assert_eq!(
+            table.act(1, Action::Raise(59)),
+            Err(TableError::InvalidAmount {
+                amount: 59,
+                min: 60,
+                max: 1000
+            })
+        );
+        table.act(1, Action::Raise(60)).unwrap();
+        table.act(2, Action::Call).unwrap();
+        table.act(0, Action::Call).unwrap();
+        assert_eq!(table.street(), Some(Street::Flop));
+        assert_eq!(table.pot(), 180);
+
+        // postflop the minimum bet is the big blind
+        assert_eq!(table.to_act(), Some(1));
+        assert_eq!(
+            table.act(1, Action::Bet(9)),
+            Err(TableError::InvalidAmount {
+                amount: 9,
+                min: 10,
+                max: 940
+            })
+        );
+        table.act(1, Action::Bet(10)).unwrap();
+        assert_eq!(
+            table.act(2, Action::Raise(19)),
+            Err(TableError::InvalidAmount {
+                amount: 19,
+                min: 20,
+                max: 940
+            })
+        );
+    }
+
+    #[test]
+    fn test_short_all_in_does_not_reopen() {
+        let mut table = table(&[1000, 1000, 130]);
+        table.start_hand().unwrap();
+        table.act(0, Action::Call).unwrap();
+        table.act(1, Action::Call).unwrap();
+        table.act(2, Action::Check).unwrap();
+
+        // flop: bet 100, an all-in for 120 is not a full raise
+        table.act(1, Action::Bet(100)).unwrap();
+        let events = table.act(2, Action::AllIn).unwrap();
+        assert_eq!(
+            events[0],
+            Event::Acted {
+                seat: 2,
+                action: Action::Raise(120),
+                amount: 120,
+                all_in: true
+            }
+        );
+        // seat 0 hasn't acted yet and may raise
+        assert_eq!(table.to_act(), Some(0));
+        table.act(0, Action::Call).unwrap();
+        // the bettor can only call or fold
+        assert_eq!(table.to_act(), Some(1));
+        assert_eq!(
+            table.act(1, Action::Raise(300)),
+            Err(TableError::IllegalAction(Action::Raise(300)))
+        );
+        assert_eq!(
+            table.act(1, Action::AllIn),
+            Err(TableError::IllegalAction(Action::AllIn))
+        );
+        table.act(1, Action::Call).unwrap();
+        assert_eq!(table.street(), Some(Street::Turn));
+    }
+
+    #[test]
+    fn test_all_in_runout() {
+        let mut table = table(&[300, 1000, 500]);
+        table.start_hand().unwrap();
+        table.act(0, Action::AllIn).unwrap();
+        table.act(1, Action::AllIn).unwrap();
+        let events = table.act(2, Action::Call).unwrap();
+
+        // the board runs out without any more action
+        assert!(table.is_hand_over());
+        assert_eq!(table.board().len(), 5);
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, Event::StreetDealt { .. }))
+                .count(),
+            3
+        );
+        assert!(events.contains(&Event::UncalledBet {
+            seat: 1,
+            amount: 500
+        }));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, Event::Shown { .. }))
+                .count(),
+            3
+        );
+        let awarded = events
+            .iter()
+            .map(|event| match event {
+                Event::PotAwarded { amount, .. } => *amount,
+                _ => 0,
+            })
+            .sum::<u64>();
+        assert_eq!(awarded, 1300);
+        assert_eq!(stacks(&table, 3).iter().sum::<u64>(), 1800);
+    }
+
+    #[test]
+    fn test_antes_and_short_blind() {
+        let mut table = Table::with_rng(
+            TableConfig::new(5, 10).seats(3).ante(2),
+            StdRng::seed_from_u64(3),
+        );
+        table.sit(0, "a", 1000).unwrap();
+        table.sit(1, "b", 1000).unwrap();
+        table.sit(2, "c", 6).unwrap();
+        let events = table.start_hand().unwrap();
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, Event::AntePosted { amount: 2, .. }))
+                .count(),
+            3
+        );
+        // the big blind is all-in for 4
+        assert!(events.contains(&Event::BlindPosted {
+            seat: 2,
+            blind: Blind::Big,
+            amount: 4
+        }));
+        assert_eq!(table.pot(), 15);
+        table.act(0, Action::Call).unwrap();
+        table.act(1, Action::Call).unwrap();
+        assert_eq!(table.street(), Some(Street::Flop));
+        assert_eq!(table.to_act(), Some(1));
+    }
+
+    #[test]
+    fn test_legal_actions() {
+        let mut table = table(&[1000, 1000, 1000]);
+        assert_eq!(table.legal_actions(), None);
+        table.start_hand().unwrap();
+        assert_eq!(
+            table.legal_actions(),
+            Some(LegalActions {
+                seat: 0,
+                call: 10,
+                bet: None,
+                raise: Some((20, 1000)),
+                all_in: true,
+            })
+        );
+        table.act(0, Action::Call).unwrap();
+        table.act(1, Action::Call).unwrap();
+        let legal = table.legal_actions().unwrap();
+        assert!(legal.can_check());
+        assert_eq!(legal.raise, Some((20, 1000)));
+        table.act(2, Action::Check).unwrap();
+        let legal = table.legal_actions().unwrap();
+        assert_eq!(legal.bet, Some((10, 990)));
+        assert_eq!(legal.raise, None);
+    }
+
+    #[test]
+    fn test_pot_limit() {
+        let mut table = Table::with_rng(
+            TableConfig::new(5, 10)
+                .seats(3)
+                .betting(BettingStructure::PotLimit),
+            StdRng::seed_from_u64(5),
+        );
+        (0..3).for_each(|seat| table.sit(seat, "p", 1000).unwrap());
+        table.start_hand().unwrap();
+        let legal = table.legal_actions().unwrap();
+        assert_eq!(legal.raise, Some((20, 35)));
+        assert!(!legal.all_in);
+        assert_eq!(
+            table.act(0, Action::Raise(40)),
+            Err(TableError::InvalidAmount {
+                amount: 40,
+                min: 20,
+                max: 35
+            })
+        );
+        assert_eq!(
+            table.act(0, Action::AllIn),
+            Err(TableError::IllegalAction(Action::AllIn))
+        );
+        table.act(0, Action::Raise(35)).unwrap();
+        // the small blind calls 30 into 50: 80 in the pot, raise 80 more to 115
+        assert_eq!(table.legal_actions().unwrap().raise, Some((60, 115)));
+        table.act(1, Action::Raise(115)).unwrap();
+        table.act(2, Action::Fold).unwrap();
+        table.act(0, Action::Call).unwrap();
+        assert_eq!(table.pot(), 240);
+        // postflop a pot sized bet
+        assert_eq!(table.legal_actions().unwrap().bet, Some((10, 240)));
+    }
+
+    #[test]
+    fn test_fixed_limit() {
+        let mut table = Table::with_rng(
+            TableConfig::new(5, 10)
+                .seats(3)
+                .betting(BettingStructure::FixedLimit {
+                    small_bet: 10,
+                    big_bet: 20,
+                    cap: 4,
+                }),
+            StdRng::seed_from_u64(5),
+        );
+        (0..3).for_each(|seat| table.sit(seat, "p", 1000).unwrap());
+        table.start_hand().unwrap();
+        assert_eq!(table.legal_actions().unwrap().raise, Some((20, 20)));
+        assert_eq!(
+            table.act(0, Action::Raise(30)),
+            Err(TableError::InvalidAmount {
+                amount: 30,
+                min: 20,
+                max: 20
+            })
+        );
+        table.act(0, Action::Raise(20)).unwrap();
+        table.act(1, Action::Raise(30)).unwrap();
+        table.act(2, Action::Raise(40)).unwrap();
+        // the big blind and three raises: capped
+        let legal = table.legal_actions().unwrap();
+        assert_eq!(legal.raise, None);
+        assert!(!legal.all_in);
+        table.act(0, Action::Call).unwrap();
+        table.act(1, Action::Call).unwrap();
+        assert_eq!(table.street(), Some(Street::Flop));
+        assert_eq!(table.legal_actions().unwrap().bet, Some((10, 10)));
+        table.act(1, Action::Check).unwrap();
+        table.act(2, Action::Check).unwrap();
+        table.act(0, Action::Check).unwrap();
+        // turn: big bets
+        assert_eq!(table.street(), Some(Street::Turn));
+        table.act(1, Action::Bet(20)).unwrap();
+        assert_eq!(table.legal_actions().unwrap().raise, Some((40, 40)));
+    }
+
+    #[test]
+    fn test_fixed_limit_short_all_in() {
+        let mut table = Table::with_rng(
+            TableConfig::new(5, 10)
+                .seats(3)
+                .betting(BettingStructure::FixedLimit {
+                    small_bet: 10,
+                    big_bet: 20,
+                    cap: 4,
+                }),
+            StdRng::seed_from_u64(5),
+        );
+        [1000, 25, 1000]
+            .into_iter()
+            .enumerate()
+            .for_each(|(seat, stack)| table.sit(seat, "p", stack).unwrap());
+        table.start_hand().unwrap();
+        table.act(0, Action::Raise(20)).unwrap();
+        // the small blind is all-in for 25, not a full raise
+        table.act(1, Action::AllIn).unwrap();
+        table.act(2, Action::Raise(35)).unwrap();
+        // the big blind and two full raises: one raise left
+        assert_eq!(table.legal_actions().unwrap().raise, Some((45, 45)));
+        table.act(0, Action::Raise(45)).unwrap();
+        assert_eq!(table.legal_actions().unwrap().raise, None);
+        table.act(2, Action::Call).unwrap();
+        assert_eq!(table.street(), Some(Street::Flop));
+    }
+
+    #[test]
+    fn test_forced_deal() {
+        let cards = |s: &str| s.parse::<Card>().unwrap();
+        let mut table = table(&[1000, 1000, 1000]);
+        let deal = Deal {
+            button: Some(2),
+            hole: vec![
+                (0, [cards("As"), cards("Ad")]),
+                (1, [cards("Kh"), cards("Kc")]),
+            ],
+            board: vec![cards("2c"), cards("7d"), cards("Ts"), cards("Ks")],
+        };
+        let events = table.start_hand_with(&deal).unwrap();
+        assert_eq!(events[0], Event::HandStarted { button: 2 });
+        assert_eq!(table.hole_cards(0), Some([cards("As"), cards("Ad")]));
+        assert_eq!(table.hole_cards(1), Some([cards("Kh"), cards("Kc")]));
+        let hole = table.hole_cards(2).unwrap();
+        assert!(hole.iter().all(|card| !deal.board.contains(card)));
+
+        table.act(2, Action::Call).unwrap();
+        table.act(0, Action::Call).unwrap();
+        table.act(1, Action::Check).unwrap();
+        assert_eq!(table.board(), &deal.board[..3]);
+        for seat in [0, 1, 2, 0, 1, 2] {
+            table.act(seat, Action::Check).unwrap();
+        }
+        // the river is not forced
+        assert_eq!(&table.board()[..4], &deal.board[..]);
+        assert!(!table.board()[..4].contains(&table.board()[4]));
+
+        assert_eq!(table.start_hand(), Err(TableError::HandInProgress));
+
+        let mut table = Table::with_rng(TableConfig::new(5, 10).seats(3), StdRng::seed_from_u64(1));
+        table.sit(0, "a", 100).unwrap();
+        table.sit(1, "b", 100).unwrap();
+        [
+            Deal {
+                hole: vec![(2, [cards("As"), cards("Ad")])],
+                ..Deal::default()
+            },
+            Deal {
+                hole: vec![(0, [cards("As"), cards("Ad")])],
+                board: vec![cards("Ad")],
+                ..Deal::default()
+            },
+        ]
+        .iter()
+        .for_each(|deal| assert_eq!(table.start_hand_with(deal), Err(TableError::InvalidDeal)));
+        let deal = Deal {
+            button: Some(3),
+            ..Deal::default()
+        };
+        assert_eq!(
+            table.start_hand_with(&deal),
+            Err(TableError::InvalidSeat(3))
+        );
+    }
+
+    #[test]
+    fn test_full_table() {
+        let mut table = self::table(&[100; MAX_PLAYERS + 1]);
+        assert_eq!(table.start_hand(), Err(TableError::TooManyPlayers));
+
+        // every card but the burns is out by the river, some seats with forced cards
+        let mut table = self::table(&[100; MAX_PLAYERS]);
+        let cards = CardSet::FULL.to_vec();
+        let deal = Deal {
+            hole: (0..12)
+                .map(|seat| (seat, [cards[2 * seat], cards[2 * seat + 1]]))
+                .collect(),
+            ..Deal::default()
+        };
+        table.start_hand_with(&deal).unwrap();
+        while let Some(seat) = table.to_act() {
+            table.act(seat, Action::AllIn).unwrap();
+        }
+        assert_eq!(table.board().len(), 5);
+        assert_eq!(table.hole_cards(11), Some([cards[22], cards[23]]));
+        let mut dealt = (0..MAX_PLAYERS)
+            .flat_map(|seat| table.hole_cards(seat).unwrap())
+            .chain(table.board().iter().copied())
+            .collect::<Vec<_>>();
+        dealt.sort();
+        dealt.dedup();
+        assert_eq!(dealt.len(), 2 * MAX_PLAYERS + 5);
+    }
+
+    #[test]
+    fn test_random_play_keeps_chips() {
+        // random legal play over many hands never loses or creates chips
+        let mut rng = StdRng::seed_from_u64(11);
+        let total = 4500;
+        for betting in [
+            BettingStructure::NoLimit,
+            BettingStructure::PotLimit,
+            BettingStructure::FixedLimit {
+                small_bet: 10,
+                big_bet: 20,
+                cap: 4,
+            },
+        ] {
+            let mut table = table(&[500, 800, 1200, 300, 1000, 700]);
+            table.config.betting = betting;
+            for _ in 0..300 {
+                if table.start_hand().is_err() {
+                    break;
+                }
+                while let Some(seat) = table.to_act() {
+                    let actions = [
+                        Action::Fold,
+                        Action::Check,
+                        Action::Call,
+                        Action::Bet(rng.gen_range(1..400)),
+                        Action::Raise(rng.gen_range(1..800)),
+                        Action::AllIn,
+                    ];
+                    let action = *actions.choose(&mut rng).unwrap();
+                    let before = table.pot();
+                    if table.act(seat, action).is_err() {
+                        // failed actions leave the table untouched
+                        assert_eq!(table.pot(), before);
+                    }
+                }
+                let stacks = (0..6)
+                    .map(|seat| table.seat(seat).unwrap().stack)
+                    .sum::<u64>();
+                assert_eq!(stacks, total);
+            }
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! Fixtures shared by the unit tests
+
+use crate::{coreutils::Card, showdown::Contender};
+
+/// Cards written back to back, like "AhKd7c"
+pub fn cards(s: &str) -> Vec<Card> {
+    Card::parse_many(s).unwrap()
+}
+
+/// Contender at seat with two hole cards, like "AsAd"
+pub fn contender(seat: usize, hole: &str) -> Contender {
+    let hole = cards(hole);
+    Contender::new(seat, [hole[0], hole[1]])
+}